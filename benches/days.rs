@@ -0,0 +1,77 @@
+//! Times each day's generator and both parts separately.
+//!
+//! Uses `input/2025/dayN.txt` when it exists and a synthetic input of similar
+//! size otherwise, so the suite runs on a fresh checkout too. Save a baseline
+//! and compare later runs against it with
+//!
+//! ```text
+//! cargo bench --bench days -- --save-baseline main
+//! cargo bench --bench days -- --baseline main
+//! ```
+
+use aoc2025::gen::{self, Rng};
+use aoc2025::registry::{self, Generator};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::path::Path;
+use std::time::Duration;
+
+/// Input shaped like each day's real one: about the same size, and the same
+/// range of values.
+fn synthetic(day: u32) -> String {
+    let mut rng = Rng::new(u64::from(day));
+    match day {
+        1 => gen::rotations(&mut rng, 4000, 400, 9).input,
+        2 => gen::id_ranges(&mut rng, 15, 15, 20_000).input,
+        3 => gen::banks(&mut rng, 200, 100).input,
+        // 35 by 35 tiles, 139 cells a side
+        4 => gen::clusters(&mut rng, 500, 112).input,
+        5 => gen::database(&mut rng, 80, 500, 500).input,
+        6 => gen::worksheet(&mut rng, 1000, 4).input,
+        _ => unreachable!("no synthetic input for day {}", day),
+    }
+}
+
+fn input(day: u32) -> (String, &'static str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("input/2025/day{}.txt", day));
+    match std::fs::read_to_string(path) {
+        Ok(input) => (input, "real"),
+        Err(_) => (synthetic(day), "synthetic"),
+    }
+}
+
+fn bench_day(c: &mut Criterion, generator: Generator) {
+    let (input, kind) = input(generator.day);
+    let parsed = generator
+        .parse(&input)
+        .unwrap_or_else(|e| panic!("day {} {} input: {}", generator.day, kind, e));
+
+    let mut group = c.benchmark_group(format!("day{}", generator.day));
+    group.bench_function(BenchmarkId::new("generator", kind), |b| {
+        b.iter(|| generator.parse(black_box(&input)).is_ok())
+    });
+    group.bench_function(BenchmarkId::new("part1", kind), |b| {
+        b.iter(|| black_box(&parsed).part1().unwrap())
+    });
+    group.bench_function(BenchmarkId::new("part2", kind), |b| {
+        b.iter(|| black_box(&parsed).part2().unwrap())
+    });
+    group.finish();
+}
+
+fn days(c: &mut Criterion) {
+    for generator in registry::generators() {
+        bench_day(c, generator);
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // a change is only reported as a regression past 5%, so noise between
+    // runs on the same machine does not drown it out
+    config = Criterion::default()
+        .noise_threshold(0.05)
+        .measurement_time(Duration::from_secs(3));
+    targets = days
+}
+criterion_main!(benches);
@@ -0,0 +1,87 @@
+//! Generates a test for every example input, `examples/dayN/NAME.txt`, see
+//! `src/examples.rs`, and for every input that crashed a fuzz target,
+//! `fuzz/regressions/TARGET/NAME`, see `src/fuzzing.rs`.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=examples");
+    println!("cargo:rerun-if-changed=fuzz/regressions");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).to_path_buf();
+    fs::write(out.join("examples.rs"), examples()).unwrap();
+    fs::write(out.join("fuzz_regressions.rs"), fuzz_regressions()).unwrap();
+}
+
+/// The files in each subdirectory of `dir`, sorted.
+fn files(dir: &str) -> Vec<(String, String)> {
+    let mut files = Vec::new();
+    for subdir in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let subdir_name = subdir.file_name().to_string_lossy().into_owned();
+        for file in fs::read_dir(subdir.path()).into_iter().flatten().flatten() {
+            if file.path().is_file() {
+                let name = file.file_name().to_string_lossy().into_owned();
+                files.push((subdir_name.clone(), name));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// `name` as part of a function name.
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn examples() -> String {
+    let mut tests = String::new();
+    for (dir, file) in files("examples") {
+        let Some(day) = dir
+            .strip_prefix("day")
+            .and_then(|day| day.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Some(stem) = file.strip_suffix(".txt") else {
+            continue;
+        };
+        writeln!(
+            tests,
+            "#[test]\nfn day{}_{}() {{\n    check({}, {:?});\n}}\n",
+            day,
+            identifier(stem),
+            day,
+            format!("examples/{}/{}", dir, file)
+        )
+        .unwrap();
+    }
+    tests
+}
+
+fn fuzz_regressions() -> String {
+    let mut tests = String::new();
+    for (target, file) in files("fuzz/regressions") {
+        writeln!(
+            tests,
+            "#[test]\nfn {}_{}() {{\n    replay({:?}, {:?});\n}}\n",
+            identifier(&target),
+            identifier(&file),
+            target,
+            format!("fuzz/regressions/{}/{}", target, file)
+        )
+        .unwrap();
+    }
+    tests
+}
@@ -0,0 +1,386 @@
+//! Confirmed answers, kept in `answers.toml` so changes to a solver that
+//! alter a real answer are caught.
+//!
+//! ```toml
+//! [[answer]]
+//! day = 5
+//! part = 1
+//! input = "input/2025/day5.txt"
+//! answer = 782
+//! ```
+//!
+//! Answers too large for a TOML integer, and text answers, are strings.
+
+use crate::registry;
+use crate::solution::Answer;
+use anyhow::{bail, Context};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+/// The confirmed answer to one part for one input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u32,
+    pub part: u32,
+    /// Path of the input file, as given to the runner.
+    pub input: String,
+    pub answer: Answer,
+}
+
+/// Every confirmed answer, ordered by day, part and input.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct AnswerRegistry {
+    entries: Vec<Entry>,
+}
+
+impl AnswerRegistry {
+    /// Reads the registry at `path`; a missing file is an empty registry.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(AnswerRegistry::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        AnswerRegistry::parse(&text).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let table: toml::Table = text.parse()?;
+        let mut registry = AnswerRegistry::default();
+
+        for (key, value) in &table {
+            if key != "answer" {
+                bail!("Unexpected key '{}'", key);
+            }
+            let entries = value
+                .as_array()
+                .context("'answer' must be an array of tables")?;
+            for (i, entry) in entries.iter().enumerate() {
+                let entry = parse_entry(entry).with_context(|| format!("Answer {}", i + 1))?;
+                let key = (entry.day, entry.part, entry.input.clone());
+                if registry.insert(entry).is_some() {
+                    bail!(
+                        "Answer {}: day {} part {} of {} is listed twice",
+                        i + 1,
+                        key.0,
+                        key.1,
+                        key.2
+                    );
+                }
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    pub fn get(&self, day: u32, part: u32, input: &str) -> Option<&Answer> {
+        self.position(day, part, input)
+            .ok()
+            .map(|i| &self.entries[i].answer)
+    }
+
+    /// Adds or replaces an entry, returning the answer it replaced.
+    pub fn insert(&mut self, entry: Entry) -> Option<Answer> {
+        match self.position(entry.day, entry.part, &entry.input) {
+            Ok(i) => Some(std::mem::replace(&mut self.entries[i], entry).answer),
+            Err(i) => {
+                self.entries.insert(i, entry);
+                None
+            }
+        }
+    }
+
+    fn position(&self, day: u32, part: u32, input: &str) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|e| (e.day, e.part, e.input.as_str()).cmp(&(day, part, input)))
+    }
+
+    pub fn to_toml(&self) -> String {
+        let mut out = String::from("# Confirmed answers, checked by `aoc2025 verify`.\n");
+        for entry in &self.entries {
+            let answer = match &entry.answer {
+                Answer::Integer(n) => match i64::try_from(*n) {
+                    Ok(n) => toml::Value::Integer(n),
+                    Err(_) => toml::Value::String(n.to_string()),
+                },
+                answer => toml::Value::String(answer.to_string()),
+            };
+            out += &format!(
+                "\n[[answer]]\nday = {}\npart = {}\ninput = {}\nanswer = {}\n",
+                entry.day,
+                entry.part,
+                toml::Value::String(entry.input.clone()),
+                answer
+            );
+        }
+        out
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.to_toml())
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn parse_entry(entry: &toml::Value) -> anyhow::Result<Entry> {
+    let table = entry.as_table().context("Not a table")?;
+    if let Some(key) = table
+        .keys()
+        .find(|k| !["day", "part", "input", "answer"].contains(&k.as_str()))
+    {
+        bail!("Unexpected key '{}'", key);
+    }
+    let number = |key: &str| -> anyhow::Result<u32> {
+        let value = table
+            .get(key)
+            .with_context(|| format!("Missing '{}'", key))?;
+        value
+            .as_integer()
+            .and_then(|n| u32::try_from(n).ok())
+            .with_context(|| format!("'{}' must be a positive integer, not {}", key, value))
+    };
+    let (day, part) = (number("day")?, number("part")?);
+    if !(1..=2).contains(&part) {
+        bail!("'part' must be 1 or 2, not {}", part);
+    }
+    let input = table
+        .get("input")
+        .context("Missing 'input'")?
+        .as_str()
+        .context("'input' must be a string")?
+        .to_string();
+    let answer = match table.get("answer").context("Missing 'answer'")? {
+        toml::Value::Integer(n) => Answer::from(*n),
+        toml::Value::String(s) => s.parse().unwrap_or_else(|e| match e {}),
+        value => bail!("'answer' must be an integer or a string, not {}", value),
+    };
+    Ok(Entry {
+        day,
+        part,
+        input,
+        answer,
+    })
+}
+
+/// How one entry fared when its solver was run again.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Match,
+    Mismatch(Answer),
+    /// The input could not be read or the solver failed.
+    Failed(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Check {
+    pub entry: Entry,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Match
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entry = &self.entry;
+        let label = format!("Day {} - Part {} ({})", entry.day, entry.part, entry.input);
+        match &self.outcome {
+            Outcome::Match => write!(f, "ok    {}: {}", label, entry.answer),
+            Outcome::Mismatch(actual) => write!(
+                f,
+                "FAIL  {}: expected {}, got {}",
+                label, entry.answer, actual
+            ),
+            Outcome::Failed(error) => write!(f, "FAIL  {}: {}", label, error),
+        }
+    }
+}
+
+/// Runs the main solver of every entry on its input and compares the
+/// answers.
+pub fn verify(registry: &AnswerRegistry) -> Vec<Check> {
+    registry
+        .entries()
+        .iter()
+        .map(|entry| {
+            let outcome = match solve(entry) {
+                Ok(actual) if actual == entry.answer => Outcome::Match,
+                Ok(actual) => Outcome::Mismatch(actual),
+                Err(e) => Outcome::Failed(format!("{:#}", e)),
+            };
+            Check {
+                entry: entry.clone(),
+                outcome,
+            }
+        })
+        .collect()
+}
+
+fn solve(entry: &Entry) -> anyhow::Result<Answer> {
+    let solver = registry::find(entry.day, entry.part, None)
+        .with_context(|| format!("No solver for day {} part {}", entry.day, entry.part))?;
+    let input = fs::read_to_string(&entry.input)
+        .with_context(|| format!("Failed to read {}", entry.input))?;
+    solver.solve(&input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc2025-answers-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn entry(day: u32, part: u32, input: &str, answer: impl Into<Answer>) -> Entry {
+        Entry {
+            day,
+            part,
+            input: input.to_string(),
+            answer: answer.into(),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut registry = AnswerRegistry::default();
+        assert_eq!(registry.insert(entry(6, 1, "b.txt", 1u64 << 63)), None);
+        assert_eq!(
+            registry.insert(entry(5, 2, "a \"quoted\".txt", 14u64)),
+            None
+        );
+        assert_eq!(registry.insert(entry(5, 1, "a.txt", "text")), None);
+        assert_eq!(
+            registry.insert(entry(5, 2, "a \"quoted\".txt", 15u64)),
+            Some(Answer::Integer(14))
+        );
+
+        let text = registry.to_toml();
+        assert_eq!(
+            text,
+            r#"# Confirmed answers, checked by `aoc2025 verify`.
+
+[[answer]]
+day = 5
+part = 1
+input = "a.txt"
+answer = "text"
+
+[[answer]]
+day = 5
+part = 2
+input = 'a "quoted".txt'
+answer = 15
+
+[[answer]]
+day = 6
+part = 1
+input = "b.txt"
+answer = "9223372036854775808"
+"#
+        );
+
+        let parsed = AnswerRegistry::parse(&text).unwrap();
+        assert_eq!(parsed, registry);
+        assert_eq!(
+            parsed
+                .entries()
+                .iter()
+                .map(|e| (e.day, e.part))
+                .collect::<Vec<_>>(),
+            [(5, 1), (5, 2), (6, 1)]
+        );
+        assert_eq!(parsed.get(6, 1, "b.txt"), Some(&Answer::from(1u64 << 63)));
+        assert_eq!(parsed.get(6, 2, "b.txt"), None);
+    }
+
+    #[test]
+    fn invalid_registries() {
+        let err = |text: &str| format!("{:#}", AnswerRegistry::parse(text).err().unwrap());
+        let entry = "[[answer]]\nday = 1\npart = 1\ninput = 'x'\nanswer = 3\n";
+        assert_eq!(
+            err(&format!("{}{}", entry, entry)),
+            "Answer 2: day 1 part 1 of x is listed twice"
+        );
+        assert_eq!(
+            err(&entry.replace("part = 1", "part = 3")),
+            "Answer 1: 'part' must be 1 or 2, not 3"
+        );
+        assert_eq!(
+            err(&entry.replace("day = 1\n", "")),
+            "Answer 1: Missing 'day'"
+        );
+        assert_eq!(
+            err(&entry.replace("answer = 3", "answer = 1.5")),
+            "Answer 1: 'answer' must be an integer or a string, not 1.5"
+        );
+        assert_eq!(
+            err(&entry.replace("input", "file")),
+            "Answer 1: Unexpected key 'file'"
+        );
+        assert_eq!(err("title = 'x'"), "Unexpected key 'title'");
+    }
+
+    #[test]
+    fn verify_reruns_solvers() {
+        let dir = temp_dir("verify");
+        let input = dir.join("day6.txt");
+        fs::write(&input, "1 2\n3 4\n+ *\n").unwrap();
+        let input = input.to_str().unwrap();
+        let path = dir.join("answers.toml");
+
+        let mut registry = AnswerRegistry::default();
+        registry.insert(entry(6, 1, input, 12u64));
+        registry.insert(entry(6, 2, input, 99u64));
+        registry.insert(entry(6, 1, "missing.txt", 1u64));
+        registry.insert(entry(7, 1, input, 1u64));
+        registry.save(&path).unwrap();
+
+        let checks = verify(&AnswerRegistry::load(&path).unwrap());
+        let outcomes: Vec<&Outcome> = checks.iter().map(|c| &c.outcome).collect();
+        // absolute paths sort before relative ones
+        assert_eq!(outcomes[0], &Outcome::Match);
+        assert!(
+            matches!(outcomes[1], Outcome::Failed(e) if e.starts_with("Failed to read missing.txt: ")),
+            "{:?}",
+            outcomes[1]
+        );
+        // read by columns, each problem is a single number: 13 + 24
+        assert_eq!(outcomes[2], &Outcome::Mismatch(Answer::Integer(13 + 24)));
+        assert_eq!(
+            outcomes[3],
+            &Outcome::Failed("No solver for day 7 part 1".into())
+        );
+        assert_eq!(checks.iter().filter(|c| c.passed()).count(), 1);
+        assert_eq!(
+            checks[2].to_string(),
+            format!("FAIL  Day 6 - Part 2 ({}): expected 99, got 37", input)
+        );
+        assert_eq!(
+            checks[0].to_string(),
+            format!("ok    Day 6 - Part 1 ({}): 12", input)
+        );
+
+        assert_eq!(
+            AnswerRegistry::load(dir.join("none.toml")).unwrap(),
+            AnswerRegistry::default()
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
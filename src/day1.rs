@@ -1,4 +1,6 @@
-use anyhow::{anyhow, Context, Result};
+use crate::error::ParseError;
+use crate::registry::Solver;
+use crate::solution::{Answer, Solution};
 use aoc_runner_derive::{aoc, aoc_generator};
 
 #[derive(Debug, Eq, PartialEq)]
@@ -8,49 +10,72 @@ enum Direction {
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Rotation {
+pub(crate) struct Rotation {
     direction: Direction,
     amount: i32,
 }
 
 #[aoc_generator(day1)]
-fn parse(input: &str) -> Result<Vec<Rotation>> {
+fn parse(input: &str) -> Result<Vec<Rotation>, ParseError> {
     input
         .lines()
-        .map(|line| {
-            let first_char = line.chars().next().context("Empty line in input")?;
+        .enumerate()
+        .map(|(n, line)| {
+            let mut chars = line.chars();
+            let first_char = chars
+                .next()
+                .ok_or_else(|| ParseError::new(1, n + 1, "Empty line in input"))?;
 
             let direction = match first_char {
                 'L' => Direction::Left,
                 'R' => Direction::Right,
-                _ => return Err(anyhow!("Invalid direction: {}", first_char)),
+                _ => {
+                    return Err(ParseError::new(
+                        1,
+                        n + 1,
+                        format!("Invalid direction: {}", first_char),
+                    )
+                    .with_column(1))
+                }
             };
 
-            let amount = line[1..]
-                .parse::<i32>()
-                .context(format!("Failed to parse amount from '{}'", &line[1..]))?;
+            let amount = chars.as_str();
+            let amount = amount
+                .parse::<u32>()
+                .ok()
+                .and_then(|amount| i32::try_from(amount).ok())
+                .ok_or_else(|| {
+                    ParseError::new(
+                        1,
+                        n + 1,
+                        format!("Failed to parse amount from '{}'", amount),
+                    )
+                    .with_column(2)
+                })?;
 
             Ok(Rotation { direction, amount })
         })
         .collect()
 }
 
+/// Wide enough that no amount a `Rotation` can hold overflows it.
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 struct State {
-    pos: i32,
-    zeroes: i32,
+    pos: i64,
+    zeroes: i64,
 }
 
 #[aoc(day1, part1)]
-fn part1(input: &[Rotation]) -> String {
+fn part1(input: &[Rotation]) -> i64 {
     let state = State { pos: 50, zeroes: 0 };
 
     input
         .iter()
         .fold(state, |mut state, rotation| {
+            let amount = i64::from(rotation.amount);
             let next_pos = match rotation.direction {
-                Direction::Left => state.pos - rotation.amount,
-                Direction::Right => state.pos + rotation.amount,
+                Direction::Left => state.pos - amount,
+                Direction::Right => state.pos + amount,
             } % 100;
 
             if next_pos == 0 {
@@ -61,42 +86,42 @@ fn part1(input: &[Rotation]) -> String {
             state
         })
         .zeroes
-        .to_string()
 }
 
 #[aoc(day1, part2)]
-fn part2(input: &[Rotation]) -> String {
+fn part2(input: &[Rotation]) -> i64 {
     let state = State { pos: 50, zeroes: 0 };
 
     input
         .iter()
         .fold(state, |mut state, rotation| {
+            let amount = i64::from(rotation.amount);
             let next_pos = match rotation.direction {
-                Direction::Left => state.pos - rotation.amount,
-                Direction::Right => state.pos + rotation.amount,
+                Direction::Left => state.pos - amount,
+                Direction::Right => state.pos + amount,
             }
             .rem_euclid(100);
 
             let zero_crosses = match rotation.direction {
                 Direction::Right => {
                     // Count how many times we pass through 0 going right
-                    (state.pos + rotation.amount) / 100
+                    (state.pos + amount) / 100
                 }
                 Direction::Left => {
                     // Count how many times we pass through 0 going left
                     if state.pos == 0 {
                         // Starting at 0, we only cross 0 again after 100 steps
-                        rotation.amount / 100
-                    } else if rotation.amount <= state.pos {
+                        amount / 100
+                    } else if amount <= state.pos {
                         // No wrap-around, only count if we land exactly on 0
-                        if (state.pos - rotation.amount) == 0 {
+                        if (state.pos - amount) == 0 {
                             1
                         } else {
                             0
                         }
                     } else {
                         // Wrap-around: first crossing + additional full rotations
-                        let steps_past_zero = rotation.amount - state.pos;
+                        let steps_past_zero = amount - state.pos;
                         1 + steps_past_zero / 100
                     }
                 }
@@ -109,23 +134,80 @@ fn part2(input: &[Rotation]) -> String {
             state
         })
         .zeroes
-        .to_string()
 }
 
+/// Turns the dial one click at a time. Returns how often a rotation ends at
+/// 0 and how often any click lands on it.
+fn clicks(input: &[Rotation]) -> (i64, i64) {
+    let (mut pos, mut stops, mut passes) = (50, 0, 0);
+    for rotation in input {
+        let step = match rotation.direction {
+            Direction::Left => 99,
+            Direction::Right => 1,
+        };
+        for _ in 0..rotation.amount {
+            pos = (pos + step) % 100;
+            if pos == 0 {
+                passes += 1;
+            }
+        }
+        if pos == 0 {
+            stops += 1;
+        }
+    }
+    (stops, passes)
+}
+
+#[aoc(day1, part1, Reference)]
+fn part1_reference(input: &[Rotation]) -> i64 {
+    clicks(input).0
+}
+
+#[aoc(day1, part2, Reference)]
+fn part2_reference(input: &[Rotation]) -> i64 {
+    clicks(input).1
+}
+
+pub(crate) struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+    type Input = Vec<Rotation>;
+
+    fn parse(input: &str) -> Result<Vec<Rotation>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Vec<Rotation>) -> anyhow::Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Vec<Rotation>) -> anyhow::Result<Answer> {
+        Ok(part2(input).into())
+    }
+}
+
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 1,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse(input)?).into()),
+    },
+    Solver {
+        day: 1,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse(input)?).into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "L68
-L30
-R48
-L5
-R60
-L55
-L1
-L99
-R14
-L82";
+    const EXAMPLE: &str = include_str!("../examples/day1/example.txt");
 
     #[test]
     fn part1_example() {
@@ -138,18 +220,57 @@ L82";
         );
     }
 
+    #[test]
+    fn malformed_input() {
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(err("L1\n\nR2"), "Day 1, line 2: Empty line in input");
+        assert_eq!(
+            err("L1\nX2"),
+            "Day 1, line 2, column 1: Invalid direction: X"
+        );
+        assert_eq!(err("é5"), "Day 1, line 1, column 1: Invalid direction: é");
+        assert_eq!(
+            err("R"),
+            "Day 1, line 1, column 2: Failed to parse amount from ''"
+        );
+        assert_eq!(
+            err("L-5"),
+            "Day 1, line 1, column 2: Failed to parse amount from '-5'"
+        );
+        assert_eq!(
+            err("R2147483648"),
+            "Day 1, line 1, column 2: Failed to parse amount from '2147483648'"
+        );
+        assert_eq!(parse("R2147483647").unwrap()[0].amount, i32::MAX);
+    }
+
+    #[test]
+    fn largest_amounts() {
+        let input = parse("R2147483647\nL2147483647").unwrap();
+        assert_eq!(part1(&input), 0);
+        // 21474836 crossings to 97, then back through 0 from 97
+        assert_eq!(part2(&input), 21474836 + 1 + (2147483647 - 97) / 100);
+    }
+
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE).unwrap()), "6");
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 6);
     }
 
     #[test]
     fn part2_example_2() {
-        assert_eq!(part2(&parse("R1000").unwrap()), "10");
+        assert_eq!(part2(&parse("R1000").unwrap()), 10);
     }
 
     #[test]
     fn part2_example_3() {
-        assert_eq!(part2(&parse("L50\nR100").unwrap()), "2");
+        assert_eq!(part2(&parse("L50\nR100").unwrap()), 2);
+    }
+
+    #[test]
+    fn reference() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(clicks(&input), (3, 6));
+        assert_eq!(clicks(&parse("L50\nR100\nL0").unwrap()), (3, 2));
     }
 }
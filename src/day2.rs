@@ -1,10 +1,13 @@
+use crate::error::{column_of, ParseError};
+use crate::registry::Solver;
+use crate::solution::{Answer, Solution};
 use anyhow::Context;
 use aoc_runner_derive::{aoc, aoc_generator};
 
 /// Represents a range of IDs to check.
 ///
 /// The range is inclusive on both ends: [start, end].
-struct Pair {
+pub(crate) struct Pair {
     start: u64,
     end: u64,
 }
@@ -19,21 +22,27 @@ fn digits_of(id: u64) -> Vec<char> {
 }
 
 #[aoc_generator(day2)]
-fn parse(input: &str) -> anyhow::Result<Vec<Pair>> {
-    input
-        .split(',')
-        .map(|pair| {
-            let i = pair.find('-').context("Failed to find delimiter '-'")?;
-            Ok(Pair {
-                start: pair[..i]
+fn parse(input: &str) -> Result<Vec<Pair>, ParseError> {
+    let mut pairs = Vec::new();
+    for (n, line) in input.lines().enumerate() {
+        for pair in line.split(',') {
+            let error = |part: &str, message: &str| {
+                ParseError::new(2, n + 1, message).with_column(column_of(line, part))
+            };
+            let (start, end) = pair
+                .split_once('-')
+                .ok_or_else(|| error(pair, "Failed to find delimiter '-'"))?;
+            pairs.push(Pair {
+                start: start
                     .parse::<u64>()
-                    .context("Failed to parse start")?,
-                end: pair[i + 1..]
+                    .map_err(|e| error(start, "Failed to parse start").with_source(e))?,
+                end: end
                     .parse::<u64>()
-                    .context("Failed to parse end")?,
-            })
-        })
-        .collect()
+                    .map_err(|e| error(end, "Failed to parse end").with_source(e))?,
+            });
+        }
+    }
+    Ok(pairs)
 }
 
 /// Checks if a number's digits can be split in half with both halves equal.
@@ -46,7 +55,7 @@ fn parse(input: &str) -> anyhow::Result<Vec<Pair>> {
 fn has_repeating_halves(id: u64) -> bool {
     let digits = digits_of(id);
 
-    if digits.len() % 2 != 0 {
+    if !digits.len().is_multiple_of(2) {
         return false;
     }
 
@@ -70,7 +79,7 @@ fn has_repeating_pattern(id: u64) -> bool {
     let digits = digits_of(id);
     (1..digits.len())
         .filter(|&chunk_size| {
-            digits.len() % chunk_size == 0
+            digits.len().is_multiple_of(chunk_size)
         })
         .any(|chunk_size| {
             let first = &digits[0..chunk_size];
@@ -98,29 +107,142 @@ fn find_ids_with_repeating_pattern(pair: &Pair) -> Vec<u64> {
         .collect()
 }
 
+/// Sum of the IDs in `pair` that have `digits` digits and repeat a sequence
+/// of `period` digits, which must divide `digits`.
+///
+/// Every such ID is the sequence times `1 0..0 1 .. 0..0 1`, so the sum is
+/// that factor times a sum of consecutive sequences.
+fn sum_with_period(pair: &Pair, digits: u32, period: u32) -> u128 {
+    let factor: u128 = (0..digits / period).map(|i| 10u128.pow(i * period)).sum();
+    let low = u128::from(pair.start)
+        .div_ceil(factor)
+        .max(10u128.pow(period - 1));
+    let high = (u128::from(pair.end) / factor).min(10u128.pow(period) - 1);
+    if low > high {
+        return 0;
+    }
+    factor * ((low + high) * (high - low + 1) / 2)
+}
+
+/// Digit counts of the IDs that can repeat a sequence.
+const DIGITS: std::ops::RangeInclusive<u32> = 2..=20;
+
+/// Sum of the IDs in `pair` whose halves are equal, without walking them.
+fn sum_repeating_halves(pair: &Pair) -> u128 {
+    DIGITS
+        .step_by(2)
+        .map(|digits| sum_with_period(pair, digits, digits / 2))
+        .sum()
+}
+
+/// Sum of the IDs in `pair` that repeat a pattern, without walking them.
+///
+/// An ID repeating with some period also repeats with `digits / q` for a
+/// prime `q`, so those periods cover every ID; inclusion-exclusion removes
+/// the ones counted twice, which repeat with `digits / (q1 * q2)`.
+fn sum_repeating_pattern(pair: &Pair) -> u128 {
+    DIGITS
+        .map(|digits| {
+            let primes: Vec<u32> = (2..=digits)
+                .filter(|&q| digits % q == 0 && (2..q).all(|r| q % r != 0))
+                .collect();
+            let sum: i128 = (1..1u32 << primes.len())
+                .map(|subset| {
+                    let product: u32 = primes
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| subset >> i & 1 == 1)
+                        .map(|(_, q)| q)
+                        .product();
+                    let sum = sum_with_period(pair, digits, digits / product) as i128;
+                    if subset.count_ones() % 2 == 1 {
+                        sum
+                    } else {
+                        -sum
+                    }
+                })
+                .sum();
+            sum as u128
+        })
+        .sum()
+}
+
+fn total(sums: impl Iterator<Item = u128>) -> anyhow::Result<u64> {
+    u64::try_from(sums.sum::<u128>())
+        .ok()
+        .context("Sum of invalid IDs overflowed u64")
+}
+
 #[aoc(day2, part1)]
-fn part1(input: &[Pair]) -> String {
+fn part1(input: &[Pair]) -> anyhow::Result<u64> {
+    total(input.iter().map(sum_repeating_halves))
+}
+
+#[aoc(day2, part2)]
+fn part2(input: &[Pair]) -> anyhow::Result<u64> {
+    total(input.iter().map(sum_repeating_pattern))
+}
+
+/// Sums the IDs whose digits split into two equal halves, testing each ID of
+/// every range in turn.
+#[aoc(day2, part1, Reference)]
+fn part1_reference(input: &[Pair]) -> u64 {
     input
         .iter()
-        .flat_map(|pair| find_ids_with_repeating_halves(pair))
-        .sum::<u64>()
-        .to_string()
+        .flat_map(find_ids_with_repeating_halves)
+        .sum()
 }
 
-#[aoc(day2, part2)]
-fn part2(input: &[Pair]) -> String {
+/// Sums the IDs made of a digit sequence repeated at least twice, testing
+/// each ID of every range against every sequence length.
+#[aoc(day2, part2, Reference)]
+fn part2_reference(input: &[Pair]) -> u64 {
     input
         .iter()
-        .flat_map(|pair| find_ids_with_repeating_pattern(pair))
-        .sum::<u64>()
-        .to_string()
+        .flat_map(find_ids_with_repeating_pattern)
+        .sum()
 }
 
+pub(crate) struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+    type Input = Vec<Pair>;
+
+    fn parse(input: &str) -> Result<Vec<Pair>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Vec<Pair>) -> anyhow::Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Vec<Pair>) -> anyhow::Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 2,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse(input)?).into()),
+    },
+    Solver {
+        day: 2,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse(input)?).into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124";
+    const INPUT: &str = include_str!("../examples/day2/example.txt");
 
     #[test]
     fn test_has_repeating_halves() {
@@ -235,11 +357,85 @@ mod tests {
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(INPUT).unwrap()), "1227775554");
+        assert_eq!(part1(&parse(INPUT).unwrap()).unwrap(), 1227775554);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(INPUT).unwrap()), "4174379265");
+        assert_eq!(part2(&parse(INPUT).unwrap()).unwrap(), 4174379265);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("11-22,95"),
+            "Day 2, line 1, column 7: Failed to find delimiter '-'"
+        );
+        assert_eq!(
+            err("11-22,\n"),
+            "Day 2, line 1, column 7: Failed to find delimiter '-'"
+        );
+        assert_eq!(
+            err("1-2\n3-x"),
+            "Day 2, line 2, column 3: Failed to parse end"
+        );
+        assert_eq!(err("-5"), "Day 2, line 1, column 1: Failed to parse start");
+        assert_eq!(err("é-5"), "Day 2, line 1, column 1: Failed to parse start");
+
+        let err = parse("1-99999999999999999999").err().unwrap();
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Day 2, line 1, column 3: Failed to parse end: number too large to fit in target type"
+        );
+
+        // ranges may also go on separate lines
+        assert_eq!(parse("11-22\n95-115,1-2\n").unwrap().len(), 3);
+    }
+
+    #[test]
+    fn overlapping_pairs_counted_per_range() {
+        // 22 and 111 are in both ranges, so they count twice
+        assert_eq!(part1(&parse("11-22,15-33").unwrap()).unwrap(), 88);
+        assert_eq!(part2(&parse("95-115,100-115").unwrap()).unwrap(), 321);
+    }
+
+    #[test]
+    fn sums_match_walking_every_id() {
+        for (start, end) in [(1, 100_000), (95, 115), (998, 1012), (111_110, 111_112)] {
+            let pair = Pair { start, end };
+            let halves: u64 = find_ids_with_repeating_halves(&pair).iter().sum();
+            let pattern: u64 = find_ids_with_repeating_pattern(&pair).iter().sum();
+            assert_eq!(sum_repeating_halves(&pair), u128::from(halves));
+            assert_eq!(sum_repeating_pattern(&pair), u128::from(pattern));
+        }
+        // 1111111111 repeats with periods 1, 2 and 5 but counts once
+        let pair = Pair {
+            start: 1111111111,
+            end: 1111111111,
+        };
+        assert_eq!(sum_repeating_pattern(&pair), 1111111111);
+        assert_eq!(sum_repeating_halves(&pair), 1111111111);
+    }
+
+    #[test]
+    fn whole_domain() {
+        let input = parse("0-18446744073709551615").unwrap();
+        assert_eq!(
+            part1(&input).err().unwrap().to_string(),
+            "Sum of invalid IDs overflowed u64"
+        );
+        // the 20 digit IDs start at 10^19, past the largest one with 19
+        assert_eq!(
+            part2(&parse("9999999999999999999-18446744073709551615").unwrap())
+                .err()
+                .unwrap()
+                .to_string(),
+            "Sum of invalid IDs overflowed u64"
+        );
+        assert_eq!(
+            part1(&parse("18446744073709551615-18446744073709551615").unwrap()).unwrap(),
+            0
+        );
     }
 }
@@ -1,19 +1,33 @@
+use crate::error::{column_of, ParseError};
+use crate::registry::Solver;
+use crate::solution::{Answer, Solution};
+use anyhow::bail;
 use aoc_runner_derive::{aoc, aoc_generator};
 #[aoc_generator(day3)]
-fn parse(input: &str) -> Vec<Vec<u64>> {
+fn parse(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
     // split to lines
     input
         .lines()
-        .map(|line| {
-            line.trim()
+        .enumerate()
+        .map(|(n, line)| {
+            let digits = line.trim();
+            let column = column_of(line, digits);
+            digits
                 .chars()
-                .map(|c| c as u64 - (b'0' as u64))
-                .collect::<Vec<u64>>()
+                .enumerate()
+                .map(|(i, c)| {
+                    c.to_digit(10).map(u64::from).ok_or_else(|| {
+                        ParseError::new(3, n + 1, format!("'{}' is not a digit", c))
+                            .with_column(column + i)
+                    })
+                })
+                .collect()
         })
-        .collect::<Vec<Vec<u64>>>()
+        .collect()
 }
 
-fn max_joltage(input: &Vec<u64>) -> usize {
+/// Largest two-digit joltage over every pair of batteries, taken in order.
+fn max_joltage(input: &[u64]) -> usize {
     let mut max: usize = 0;
 
     for i in 0..input.len() {
@@ -27,26 +41,24 @@ fn max_joltage(input: &Vec<u64>) -> usize {
     max
 }
 
-fn max_joltage_rec(input: &Vec<u64>, len: &u64) -> u64 {
+fn max_joltage_rec(input: &[u64], len: usize) -> u64 {
     // between 0 and input.len() - len - 1 find leftmost maximum digit
-    max_rec_helper(input, 0, *len, vec![])
+    max_rec_helper(input, 0, len, vec![])
 }
 
 fn max_rec_helper(
-    input: &Vec<u64>,
+    input: &[u64],
     start_idx: usize,
-    remaining: u64,
-    mut current_idx: Vec<u8>,
+    remaining: usize,
+    mut current_idx: Vec<usize>,
 ) -> u64 {
     if remaining == 0 {
-        let n = current_idx
-            .iter()
-            .fold(0, |acc, &x| acc * 10 + input[x as usize] as u64);
+        let n = current_idx.iter().fold(0, |acc, &x| acc * 10 + input[x]);
         return n;
     }
 
     // find leftmost maximum digit
-    let end_idx = (input.len()) as usize - remaining as usize;
+    let end_idx = input.len() - remaining;
     let (i, _digit) = input[start_idx..=end_idx]
         .iter()
         .enumerate()
@@ -54,14 +66,7 @@ fn max_rec_helper(
         .max_by_key(|(_, &digit)| digit)
         .unwrap();
 
-    // 811111111111119
-    // 012345678901234
-    // println!(
-    //     "start_idx: {}, end_idx: {}, i: {}, digit: {}",
-    //     start_idx, end_idx, i, digit
-    // );
-
-    current_idx.push((start_idx + i) as u8);
+    current_idx.push(start_idx + i);
 
     max_rec_helper(input, start_idx + i + 1, remaining - 1, current_idx)
 }
@@ -70,57 +75,178 @@ fn max_rec_helper(
 // 2^32  =              4,294,967,296
 // 2^64  = 18,446,744,073,709,551,616
 
+/// Largest joltage of `count` batteries, one battery at a time: the best
+/// `k` batteries so far either leave out the next one or end with it.
+fn max_joltage_dp(input: &[u64], count: usize) -> u64 {
+    let mut best = vec![None; count + 1];
+    best[0] = Some(0);
+    for &digit in input {
+        for k in (1..=count).rev() {
+            if let Some(joltage) = best[k - 1] {
+                best[k] = best[k].max(Some(joltage * 10 + digit));
+            }
+        }
+    }
+    best[count].unwrap_or(0)
+}
+
+/// Batteries turned on in each bank in part 1.
+const PART1_BATTERIES: usize = 2;
+
+/// Batteries turned on in each bank in part 2.
+const PART2_BATTERIES: usize = 12;
+
+/// Sums the joltage of every bank, which must hold at least `batteries`
+/// batteries.
+fn sum_joltages(
+    input: &[Vec<u64>],
+    batteries: usize,
+    joltage: impl Fn(&[u64]) -> u64,
+) -> anyhow::Result<u64> {
+    input.iter().enumerate().try_fold(0, |acc, (n, row)| {
+        if row.len() < batteries {
+            bail!(
+                "Line {}: bank has {} batteries, fewer than {}",
+                n + 1,
+                row.len(),
+                batteries
+            );
+        }
+        Ok(acc + joltage(row))
+    })
+}
+
 #[aoc(day3, part1)]
-fn part1(input: &Vec<Vec<u64>>) -> String {
-    input
-        .into_iter()
-        .fold(0, |acc, row| acc + max_joltage(&row))
-        .to_string()
+fn part1(input: &[Vec<u64>]) -> anyhow::Result<u64> {
+    sum_joltages(input, PART1_BATTERIES, |row| {
+        max_joltage_rec(row, PART1_BATTERIES)
+    })
+}
+
+#[aoc(day3, part1, Reference)]
+fn part1_reference(input: &[Vec<u64>]) -> anyhow::Result<u64> {
+    sum_joltages(input, PART1_BATTERIES, |row| max_joltage(row) as u64)
 }
 
 #[aoc(day3, part2)]
-fn part2(input: &Vec<Vec<u64>>) -> String {
-    input
-        .into_iter()
-        .fold(0, |acc, row| acc + max_joltage_rec(&row, &12))
-        .to_string()
+fn part2(input: &[Vec<u64>]) -> anyhow::Result<u64> {
+    sum_joltages(input, PART2_BATTERIES, |row| {
+        max_joltage_rec(row, PART2_BATTERIES)
+    })
+}
+
+#[aoc(day3, part2, Reference)]
+fn part2_reference(input: &[Vec<u64>]) -> anyhow::Result<u64> {
+    sum_joltages(input, PART2_BATTERIES, |row| {
+        max_joltage_dp(row, PART2_BATTERIES)
+    })
+}
+
+pub(crate) struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+    type Input = Vec<Vec<u64>>;
+
+    fn parse(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Vec<Vec<u64>>) -> anyhow::Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Vec<Vec<u64>>) -> anyhow::Result<Answer> {
+        Ok(part2(input)?.into())
+    }
 }
 
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 3,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 3,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse(input)?)?.into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const EXAMPLE: &str = include_str!("../examples/day3/example.txt");
+
     #[test]
     fn part1_example() {
-        assert_eq!(
-            part1(&parse(
-                "987654321111111
-        811111111111119
-        234234234234278
-        818181911112111"
-            )),
-            "357"
-        );
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()).unwrap(), 357);
     }
 
     #[test]
     fn part2_example() {
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()).unwrap(), 3121910778619);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("12\n  3x4"),
+            "Day 3, line 2, column 4: 'x' is not a digit"
+        );
+        assert_eq!(err("9 9"), "Day 3, line 1, column 2: ' ' is not a digit");
+        assert_eq!(err("1é"), "Day 3, line 1, column 2: 'é' is not a digit");
+        assert_eq!(err("١٢"), "Day 3, line 1, column 1: '١' is not a digit");
+
+        let short = parse("987654321111111\n98765432111").unwrap();
+        assert_eq!(part1(&short).unwrap(), 98 + 98);
         assert_eq!(
-            part2(&parse(
-                "987654321111111
-        811111111111119
-        234234234234278
-        818181911112111"
-            )),
-            "3121910778619"
+            part2(&short).err().unwrap().to_string(),
+            "Line 2: bank has 11 batteries, fewer than 12"
         );
+        let short = parse("98\n7\n65").unwrap();
+        for part1 in [part1, part1_reference] {
+            assert_eq!(
+                part1(&short).err().unwrap().to_string(),
+                "Line 2: bank has 1 batteries, fewer than 2"
+            );
+        }
     }
 
     #[test]
     fn part2_single() {
-        assert_eq!(part2(&parse("987654321111111")), "987654321111");
-        assert_eq!(part2(&parse("811111111111119")), "811111111119");
-        assert_eq!(part2(&parse("234234234234278")), "434234234278");
-        assert_eq!(part2(&parse("818181911112111")), "888911112111");
+        assert_eq!(
+            part2(&parse("987654321111111").unwrap()).unwrap(),
+            987654321111
+        );
+        assert_eq!(
+            part2(&parse("811111111111119").unwrap()).unwrap(),
+            811111111119
+        );
+        assert_eq!(
+            part2(&parse("234234234234278").unwrap()).unwrap(),
+            434234234278
+        );
+        assert_eq!(
+            part2(&parse("818181911112111").unwrap()).unwrap(),
+            888911112111
+        );
+    }
+    #[test]
+    fn long_banks() {
+        // indexes past 255 used to wrap around
+        let mut bank = vec![1; 300];
+        bank[280] = 9;
+        bank[299] = 8;
+        assert_eq!(max_joltage_rec(&bank, 2), 98);
+        assert_eq!(max_joltage_rec(&bank, 12), 911111111118);
+        assert_eq!(max_joltage_dp(&bank, 12), 911111111118);
+        assert_eq!(max_joltage(&bank), 98);
     }
 }
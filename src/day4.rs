@@ -1,21 +1,47 @@
+use crate::error::{column_of, ParseError};
+use crate::registry::Solver;
+use crate::solution::{Answer, Solution};
 use aoc_runner_derive::{aoc, aoc_generator};
 use std::collections::HashMap;
 
-type Board = HashMap<(i16, i16), bool>;
+pub(crate) type Board = HashMap<(i16, i16), bool>;
+
+/// Coordinates stay below `i16::MAX` so that neighbours do too.
+const MAX_SIZE: usize = i16::MAX as usize;
 
 #[aoc_generator(day4)]
-fn parse(input: &str) -> Board {
+fn parse(input: &str) -> Result<Board, ParseError> {
     let mut board = Board::new();
     for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.trim().chars().enumerate() {
-            if c == '@' {
-                board.insert((x as i16, y as i16), true);
+        if y >= MAX_SIZE {
+            return Err(ParseError::new(
+                4,
+                y + 1,
+                format!("board is taller than {} lines", MAX_SIZE),
+            ));
+        }
+        let row = line.trim();
+        let column = column_of(line, row);
+        for (x, c) in row.chars().enumerate() {
+            let error =
+                |message: String| ParseError::new(4, y + 1, message).with_column(column + x);
+            if x >= MAX_SIZE {
+                return Err(error(format!("board is wider than {} columns", MAX_SIZE)));
+            }
+            match c {
+                '@' => {
+                    board.insert((x as i16, y as i16), true);
+                }
+                '.' => {}
+                _ => return Err(error(format!("'{}' is not '@' or '.'", c))),
             }
         }
     }
-    board
+    Ok(board)
 }
 
+/// The rolls that can be removed right now, checking every roll's
+/// neighbours.
 fn to_remove(input: &Board) -> Vec<(i16, i16)> {
     let mut pos: Vec<(i16, i16)> = Vec::new();
 
@@ -53,13 +79,76 @@ fn to_remove(input: &Board) -> Vec<(i16, i16)> {
     pos
 }
 
+fn neighbours((x, y): (i16, i16)) -> impl Iterator<Item = (i16, i16)> {
+    [
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+        (-1, 0),
+        (1, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+    ]
+    .into_iter()
+    .map(move |(dx, dy)| (x + dx, y + dy))
+}
+
+/// How many rolls are next to each roll.
+fn neighbour_counts(input: &Board) -> HashMap<(i16, i16), usize> {
+    input
+        .keys()
+        .map(|&pos| {
+            let count = neighbours(pos).filter(|n| input.contains_key(n)).count();
+            (pos, count)
+        })
+        .collect()
+}
+
 #[aoc(day4, part1)]
-fn part1(input: &Board) -> String {
-    to_remove(input).len().to_string()
+fn part1(input: &Board) -> usize {
+    neighbour_counts(input)
+        .values()
+        .filter(|&&count| count < 4)
+        .count()
 }
 
+/// Removes rolls as soon as they can be, only rechecking the neighbours of
+/// removed ones. Removing a roll never blocks another, so the order does not
+/// change the total.
 #[aoc(day4, part2)]
-fn part2(input: &Board) -> String {
+fn part2(input: &Board) -> usize {
+    let mut counts = neighbour_counts(input);
+    let mut queue: Vec<(i16, i16)> = counts
+        .iter()
+        .filter(|(_, &count)| count < 4)
+        .map(|(&pos, _)| pos)
+        .collect();
+    let mut removed = 0;
+    while let Some(pos) = queue.pop() {
+        counts.remove(&pos);
+        removed += 1;
+        for neighbour in neighbours(pos) {
+            if let Some(count) = counts.get_mut(&neighbour) {
+                *count -= 1;
+                // only once, when it drops below 4
+                if *count == 3 {
+                    queue.push(neighbour);
+                }
+            }
+        }
+    }
+    removed
+}
+
+#[aoc(day4, part1, Reference)]
+fn part1_reference(input: &Board) -> usize {
+    to_remove(input).len()
+}
+
+/// Removes every accessible roll, then checks the whole board again.
+#[aoc(day4, part2, Reference)]
+fn part2_reference(input: &Board) -> usize {
     let mut next: Board = input.clone();
 
     let mut total = 0;
@@ -74,31 +163,79 @@ fn part2(input: &Board) -> String {
             next.remove(&(x, y));
         }
     }
-    total.to_string()
+    total
 }
 
+pub(crate) struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+    type Input = Board;
+
+    fn parse(input: &str) -> Result<Board, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Board) -> anyhow::Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Board) -> anyhow::Result<Answer> {
+        Ok(part2(input).into())
+    }
+}
+
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 4,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse(input)?).into()),
+    },
+    Solver {
+        day: 4,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse(input)?).into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "..@@.@@@@.
-    @@@.@.@.@@
-    @@@@@.@.@@
-    @.@@@@..@.
-    @@.@@@@.@@
-    .@@@@@@@.@
-    .@.@.@.@@@
-    @.@@@.@@@@
-    .@@@@@@@@.
-    @.@.@@@.@.";
+    const EXAMPLE: &str = include_str!("../examples/day4/example.txt");
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE)), "13");
+        assert_eq!(part1(&parse(EXAMPLE).unwrap()), 13);
     }
 
     #[test]
     fn part2_example() {
-        assert_eq!(part2(&parse(EXAMPLE)), "43");
+        assert_eq!(part2(&parse(EXAMPLE).unwrap()), 43);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("..@\n  .#."),
+            "Day 4, line 2, column 4: '#' is not '@' or '.'"
+        );
+        assert_eq!(err("@ @"), "Day 4, line 1, column 2: ' ' is not '@' or '.'");
+
+        let wide = ".".repeat(MAX_SIZE + 1);
+        assert_eq!(
+            err(&wide),
+            "Day 4, line 1, column 32768: board is wider than 32767 columns"
+        );
+        assert_eq!(parse(&wide[1..]).unwrap().len(), 0);
+        let tall = "@\n".repeat(MAX_SIZE + 1);
+        assert_eq!(
+            err(&tall),
+            "Day 4, line 32768: board is taller than 32767 lines"
+        );
     }
 }
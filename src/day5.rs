@@ -1,14 +1,124 @@
+use crate::error::{column_of, ParseError};
+use crate::range_set::{DynamicRangeSet, Integer, RangeSet};
+use crate::registry::Solver;
+use crate::solution::{join, Answer, Solution};
+use anyhow::{anyhow, bail, Context};
 use aoc_runner_derive::{aoc, aoc_generator};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
 
 type IngredientsDatabase = Vec<(u64, u64)>;
 
-struct Day5Input {
+pub(crate) struct Day5Input {
     ingredients_database: IngredientsDatabase,
+    /// 1-based input line of each entry in `ingredients_database`.
+    database_lines: Vec<usize>,
     available_ingredients: Vec<u64>,
+    /// From a `# window: <range>` line: the IDs `Coverage` looks at.
+    window: Option<(u64, u64)>,
+}
+
+/// How `parse_with` treats ranges written end-first, such as `10-5`.
+#[derive(Debug, Default, Clone, Copy)]
+struct ParseOptions {
+    /// Swap the ends of reversed ranges instead of rejecting them.
+    normalise_reversed: bool,
 }
 
 #[aoc_generator(day5)]
-fn parse(input: &str) -> Day5Input {
+fn parse(input: &str) -> Result<Day5Input, ParseError> {
+    parse_with(input, ParseOptions::default())
+}
+
+/// Parses one database entry into an inclusive `(start, end)` interval.
+///
+/// # Examples
+/// - `3-5` → `(3, 5)`
+/// - `42` → `(42, 42)`
+/// - `100-` → `(100, u64::MAX)`
+/// - `[3,5)` → `(3, 4)`, and likewise `(3,5]`, `(3,5)` and `[3,5]`
+/// - `100+20` → `(100, 119)`, a start and a length
+fn parse_range(spec: &str, options: ParseOptions) -> anyhow::Result<(u64, u64)> {
+    let parse_id = |s: &str| -> anyhow::Result<u64> {
+        s.trim()
+            .parse::<u64>()
+            .with_context(|| format!("Failed to parse ID '{}'", s.trim()))
+    };
+
+    if let Some(open) = spec.strip_prefix(['[', '(']) {
+        let (body, close) = open
+            .strip_suffix(']')
+            .map(|body| (body, ']'))
+            .or_else(|| open.strip_suffix(')').map(|body| (body, ')')))
+            .context("Missing closing ']' or ')'")?;
+        let (start, end) = body
+            .split_once(',')
+            .context("Failed to find delimiter ','")?;
+        let (start, end) = (parse_id(start)?, parse_id(end)?);
+
+        let first = if spec.starts_with('[') {
+            Some(start)
+        } else {
+            start.checked_add(1)
+        };
+        let last = if close == ']' {
+            Some(end)
+        } else {
+            end.checked_sub(1)
+        };
+        return match (first, last) {
+            (Some(first), Some(last)) if first <= last => Ok((first, last)),
+            _ => bail!("Interval {} contains no IDs", spec),
+        };
+    }
+
+    if let Some((start, length)) = spec.split_once('+') {
+        let start = parse_id(start)?;
+        let length = parse_id(length)?;
+        if length == 0 {
+            bail!("Range {} has zero length", spec);
+        }
+        let end = start
+            .checked_add(length - 1)
+            .with_context(|| format!("Range {} runs past {}", spec, u64::MAX))?;
+        return Ok((start, end));
+    }
+
+    match spec.split_once('-') {
+        Some((start, "")) => Ok((parse_id(start)?, u64::MAX)),
+        Some((start, end)) => {
+            let (start, end) = (parse_id(start)?, parse_id(end)?);
+            if start > end && !options.normalise_reversed {
+                bail!(
+                    "Reversed range {}: start {} is after end {}",
+                    spec,
+                    start,
+                    end
+                );
+            }
+            Ok((start.min(end), start.max(end)))
+        }
+        None => {
+            let id = parse_id(spec)?;
+            Ok((id, id))
+        }
+    }
+}
+
+/// Trims a line and drops everything after `#`.
+///
+/// Returns `None` for lines that held only a comment, so callers can tell
+/// them apart from genuinely blank lines.
+fn strip_comment(line: &str) -> Option<&str> {
+    match line.split_once('#') {
+        Some((line, _)) if line.trim().is_empty() => None,
+        Some((line, _)) => Some(line.trim()),
+        None => Some(line.trim()),
+    }
+}
+
+fn parse_with(input: &str, options: ParseOptions) -> Result<Day5Input, ParseError> {
     enum Mode {
         Database,
         Ingredients,
@@ -16,151 +126,1249 @@ fn parse(input: &str) -> Day5Input {
 
     let mut mode = Mode::Database;
     let mut ingredients_database = IngredientsDatabase::new();
+    let mut database_lines = Vec::new();
     let mut available_ingredients = Vec::new();
+    let mut window = None;
+
+    for (n, raw) in input.lines().enumerate() {
+        let comment = raw.trim().strip_prefix('#').map(str::trim);
+        if let Some(spec) = comment.and_then(|c| c.strip_prefix("window:")) {
+            let spec = spec.trim();
+            let range = parse_range(spec, options).map_err(|e| {
+                ParseError::new(5, n + 1, format!("invalid window '{}'", spec))
+                    .with_column(column_of(raw, spec))
+                    .with_source(e)
+            })?;
+            window = Some(range);
+        }
+        // comment-only lines are skipped so they don't end the database section
+        let Some(line) = strip_comment(raw) else {
+            continue;
+        };
+        let error =
+            |message: String| ParseError::new(5, n + 1, message).with_column(column_of(raw, line));
 
-    for line in input.lines().map(|line| line.trim()) {
         match mode {
             Mode::Database => {
-                if line == "" {
+                if line.is_empty() {
                     mode = Mode::Ingredients;
                 } else {
-                    let range = line.split_once('-').unwrap();
-                    let start = range.0.parse().unwrap();
-                    let end = range.1.parse().unwrap();
-                    ingredients_database.push((start, end));
+                    let range = parse_range(line, options)
+                        .map_err(|e| error(format!("invalid range '{}'", line)).with_source(e))?;
+                    ingredients_database.push(range);
+                    database_lines.push(n + 1);
                 }
             }
             Mode::Ingredients => {
-                available_ingredients.push(line.parse().unwrap());
+                if line.is_empty() {
+                    continue;
+                }
+                let id = line
+                    .parse()
+                    .map_err(|e| error(format!("invalid ingredient '{}'", line)).with_source(e))?;
+                available_ingredients.push(id);
             }
         }
     }
 
-    Day5Input {
+    Ok(Day5Input {
         ingredients_database,
+        database_lines,
         available_ingredients,
-    }
+        window,
+    })
 }
 
 #[aoc(day5, part1)]
-fn part1(input: &Day5Input) -> String {
-    let mut result = 0;
-    for i in input.available_ingredients.iter() {
-        for (start, end) in &input.ingredients_database {
-            if i >= start && i <= end {
-                result += 1;
-                break;
+fn part1(input: &Day5Input) -> usize {
+    let fresh = RangeSet::from_ranges(input.ingredients_database.iter().copied());
+
+    input
+        .available_ingredients
+        .iter()
+        .filter(|&&id| fresh.contains(id))
+        .count()
+}
+
+#[aoc(day5, part2)]
+fn part2(input: &Day5Input) -> u128 {
+    RangeSet::from_ranges(input.ingredients_database.iter().copied()).len()
+}
+
+/// Counts the available ingredients inside any range, scanning the whole
+/// database for each one.
+#[aoc(day5, part1, Reference)]
+fn part1_reference(input: &Day5Input) -> usize {
+    input
+        .available_ingredients
+        .iter()
+        .filter(|&&id| {
+            input
+                .ingredients_database
+                .iter()
+                .any(|&(start, end)| start <= id && id <= end)
+        })
+        .count()
+}
+
+/// Sorts the ranges and counts each ID past the last one counted, so IDs in
+/// overlapping ranges count once.
+#[aoc(day5, part2, Reference)]
+fn part2_reference(input: &Day5Input) -> u128 {
+    let mut ranges = input.ingredients_database.clone();
+    ranges.sort_unstable();
+    let mut counted: Option<u64> = None;
+    let mut total = 0;
+    for (start, end) in ranges {
+        let from = match counted {
+            Some(last) if last >= end => continue,
+            Some(last) if last >= start => last + 1,
+            _ => start,
+        };
+        if from <= end {
+            total += u128::from(end - from) + 1;
+            counted = Some(end);
+        }
+    }
+    total
+}
+
+/// Freshness of a single available ingredient.
+#[derive(Debug, PartialEq, Eq)]
+struct IngredientStatus {
+    id: u64,
+    /// Input lines of every database range containing `id`.
+    matched_lines: Vec<usize>,
+}
+
+impl IngredientStatus {
+    fn fresh(&self) -> bool {
+        !self.matched_lines.is_empty()
+    }
+}
+
+/// A database range that contains none of the available ingredients.
+#[derive(Debug, PartialEq, Eq)]
+struct UnmatchedRange {
+    line: usize,
+    start: u64,
+    end: u64,
+}
+
+/// Audit of which database lines made each available ingredient fresh.
+#[derive(Debug, PartialEq, Eq)]
+struct FreshnessReport {
+    ingredients: Vec<IngredientStatus>,
+    unmatched_ranges: Vec<UnmatchedRange>,
+}
+
+impl FreshnessReport {
+    fn new(input: &Day5Input) -> Self {
+        let mut matched = vec![false; input.ingredients_database.len()];
+
+        let ingredients = input
+            .available_ingredients
+            .iter()
+            .map(|&id| {
+                let matched_lines = input
+                    .ingredients_database
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &(start, end))| start <= id && id <= end)
+                    .map(|(i, _)| {
+                        matched[i] = true;
+                        input.database_lines[i]
+                    })
+                    .collect();
+                IngredientStatus { id, matched_lines }
+            })
+            .collect();
+
+        let unmatched_ranges = input
+            .ingredients_database
+            .iter()
+            .zip(&input.database_lines)
+            .zip(&matched)
+            .filter(|(_, &matched)| !matched)
+            .map(|((&(start, end), &line), _)| UnmatchedRange { line, start, end })
+            .collect();
+
+        FreshnessReport {
+            ingredients,
+            unmatched_ranges,
+        }
+    }
+
+    fn fresh_count(&self) -> usize {
+        self.ingredients.iter().filter(|i| i.fresh()).count()
+    }
+
+    /// Renders the report as aligned text columns followed by the unmatched ranges.
+    fn to_table(&self) -> String {
+        let rows: Vec<(String, &str, String)> = self
+            .ingredients
+            .iter()
+            .map(|i| {
+                let lines = if i.matched_lines.is_empty() {
+                    "-".to_string()
+                } else {
+                    join(&i.matched_lines, ", ")
+                };
+                (
+                    i.id.to_string(),
+                    if i.fresh() { "yes" } else { "no" },
+                    lines,
+                )
+            })
+            .collect();
+        let width = rows.iter().map(|r| r.0.len()).max().unwrap_or(0).max(2);
+
+        let mut out = format!("{:<width$}  FRESH  LINES\n", "ID");
+        for (id, fresh, lines) in rows {
+            out += &format!("{:<width$}  {:<5}  {}\n", id, fresh, lines);
+        }
+        out += &format!(
+            "{} of {} ingredients fresh\n",
+            self.fresh_count(),
+            self.ingredients.len()
+        );
+
+        out += &format!(
+            "\n{} ranges matched no ingredient\n",
+            self.unmatched_ranges.len()
+        );
+        for r in &self.unmatched_ranges {
+            out += &format!("  line {}: {}-{}\n", r.line, r.start, r.end);
+        }
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let ingredients: Vec<String> = self
+            .ingredients
+            .iter()
+            .map(|i| {
+                format!(
+                    r#"{{"id":{},"fresh":{},"lines":[{}]}}"#,
+                    i.id,
+                    i.fresh(),
+                    join(&i.matched_lines, ",")
+                )
+            })
+            .collect();
+        let unmatched: Vec<String> = self
+            .unmatched_ranges
+            .iter()
+            .map(|r| {
+                format!(
+                    r#"{{"line":{},"start":{},"end":{}}}"#,
+                    r.line, r.start, r.end
+                )
+            })
+            .collect();
+
+        format!(
+            r#"{{"ingredients":[{}],"unmatched_ranges":[{}]}}"#,
+            ingredients.join(","),
+            unmatched.join(",")
+        )
+    }
+
+    /// Renders two CSV tables separated by a blank line: the ingredients,
+    /// with matching lines joined by `;`, then the unmatched ranges.
+    fn to_csv(&self) -> String {
+        let mut out = "id,fresh,lines\n".to_string();
+        for i in &self.ingredients {
+            out += &format!("{},{},{}\n", i.id, i.fresh(), join(&i.matched_lines, ";"));
+        }
+        out += "\nline,start,end\n";
+        for r in &self.unmatched_ranges {
+            out += &format!("{},{},{}\n", r.line, r.start, r.end);
+        }
+        out
+    }
+}
+
+#[aoc(day5, part1, Report)]
+fn part1_report(input: &Day5Input) -> String {
+    format!("\n{}", FreshnessReport::new(input).to_table())
+}
+
+#[aoc(day5, part1, ReportJson)]
+fn part1_report_json(input: &Day5Input) -> String {
+    FreshnessReport::new(input).to_json()
+}
+
+#[aoc(day5, part1, ReportCsv)]
+fn part1_report_csv(input: &Day5Input) -> String {
+    format!("\n{}", FreshnessReport::new(input).to_csv())
+}
+
+/// Coverage of a query window by the database ranges.
+///
+/// Everything is computed in one sweep over the ranges sorted by start,
+/// keeping the ends of the ranges covering the current ID in a min-heap.
+/// Ranges are clipped to the window first, so lengths and depths only
+/// describe the part of the database inside it.
+#[derive(Debug, PartialEq, Eq)]
+struct Coverage {
+    window: (u64, u64),
+    /// Consecutive `(start, end, depth)` segments tiling the whole window,
+    /// where depth is the number of ranges covering every ID in the segment.
+    segments: Vec<(u64, u64, usize)>,
+    /// `(count, lower)` buckets of range lengths: `count` ranges have a
+    /// length in `[lower, 2 * lower)`.
+    length_histogram: Vec<(usize, u128)>,
+}
+
+impl Coverage {
+    fn new(database: &[(u64, u64)], window: (u64, u64)) -> Self {
+        let (lo, hi) = window;
+        let mut ranges: Vec<(u64, u64)> = database
+            .iter()
+            .filter(|&&(start, end)| start <= hi && end >= lo)
+            .map(|&(start, end)| (start.max(lo), end.min(hi)))
+            .collect();
+        ranges.sort_unstable();
+
+        let mut segments = Vec::new();
+        let mut histogram = [0usize; 65];
+        let mut active = BinaryHeap::new();
+        let mut next = ranges.iter().peekable();
+
+        // positions are i128 so `start - 1` and `end + 1` never overflow
+        let mut pos = lo as i128;
+        while pos <= hi as i128 {
+            // admit every range starting here and drop every range that has ended
+            while let Some(&&(start, end)) = next.peek() {
+                if start as i128 > pos {
+                    break;
+                }
+                active.push(Reverse(end));
+                histogram[(u128::BITS - 1 - u64::span(start, end).leading_zeros()) as usize] += 1;
+                next.next();
+            }
+            while active
+                .peek()
+                .is_some_and(|&Reverse(end)| (end as i128) < pos)
+            {
+                active.pop();
             }
+
+            // the depth stays the same until the next range starts or an active one ends
+            let mut end = hi as i128;
+            if let Some(&&(start, _)) = next.peek() {
+                end = end.min(start as i128 - 1);
+            }
+            if let Some(&Reverse(active_end)) = active.peek() {
+                end = end.min(active_end as i128);
+            }
+
+            segments.push((pos as u64, end as u64, active.len()));
+            pos = end + 1;
+        }
+
+        let length_histogram = histogram
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(bits, &count)| (count, 1u128 << bits))
+            .collect();
+
+        Coverage {
+            window,
+            segments,
+            length_histogram,
+        }
+    }
+
+    /// Uncovered `(start, end)` gaps inside the window.
+    fn gaps(&self) -> Vec<(u64, u64)> {
+        self.segments
+            .iter()
+            .filter(|&&(_, _, depth)| depth == 0)
+            .map(|&(start, end, _)| (start, end))
+            .collect()
+    }
+
+    /// The longest gap, or the first of them on a tie.
+    fn largest_gap(&self) -> Option<(u64, u64)> {
+        self.gaps()
+            .into_iter()
+            .rev()
+            .max_by_key(|&(start, end)| u64::span(start, end))
+    }
+
+    /// Number of ranges covering `id`, or zero outside the window.
+    fn depth_at(&self, id: u64) -> usize {
+        let i = self.segments.partition_point(|&(_, end, _)| end < id);
+        match self.segments.get(i) {
+            Some(&(start, _, depth)) if start <= id => depth,
+            _ => 0,
         }
     }
-    result.to_string()
+
+    fn max_depth(&self) -> usize {
+        self.segments
+            .iter()
+            .map(|&(_, _, depth)| depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn covered(&self) -> u128 {
+        self.segments
+            .iter()
+            .filter(|&&(_, _, depth)| depth > 0)
+            .map(|&(start, end, _)| u64::span(start, end))
+            .sum()
+    }
+
+    /// Human-readable summary, including the depth at each of `ids`.
+    fn summary(&self, ids: &[u64]) -> String {
+        let (lo, hi) = self.window;
+        let mut out = format!(
+            "covered: {} of {} IDs in {}-{}\n",
+            self.covered(),
+            u64::span(lo, hi),
+            lo,
+            hi
+        );
+
+        let gaps = self.gaps();
+        out += &format!("gaps: {}\n", gaps.len());
+        for (start, end) in &gaps {
+            out += &format!("  {}-{}\n", start, end);
+        }
+        if let Some((start, end)) = self.largest_gap() {
+            out += &format!(
+                "largest gap: {}-{} ({} IDs)\n",
+                start,
+                end,
+                u64::span(start, end)
+            );
+        }
+
+        let max_depth = self.max_depth();
+        out += &format!("max overlap depth: {}\n", max_depth);
+        for (start, end, _) in self.segments.iter().filter(|s| s.2 == max_depth) {
+            out += &format!("  {}-{}\n", start, end);
+        }
+
+        if !ids.is_empty() {
+            out += "depth at ingredient:\n";
+            for &id in ids {
+                out += &format!("  {}: {}\n", id, self.depth_at(id));
+            }
+        }
+
+        out += "range lengths:\n";
+        for &(count, lower) in &self.length_histogram {
+            out += &format!("  {}-{}: {}\n", lower, 2 * lower - 1, count);
+        }
+        out
+    }
 }
 
-#[aoc(day5, part2)]
-fn part2(input: &Day5Input) -> String {
-    // sort intervals by start
-    let mut intervals = input.ingredients_database.clone();
-    intervals.sort_by_key(|(start, _)| *start);
-
-    #[derive(Debug)]
-    struct Acc {
-        current: (u64, u64),
-        result: Vec<(u64, u64)>,
-    }
-
-    // fold over intervals and see if we can combine current with next one
-    let mut acc = intervals.iter().fold(
-        Acc {
-            current: intervals[0],
-            result: vec![],
-        },
-        |mut acc, (start, end)| {
-            // if next starts before current ends
-            // we have overlap and need to extend current one
-            // join 2 intervals
-            if *start <= acc.current.1 {
-                acc.current.1 = acc.current.1.max(*end);
-            } else {
-                // no overlap, push current to acc results
-                // and assign new current
-                acc.result.push(acc.current);
-                acc.current = (*start, *end);
+/// Coverage of the input's `# window:` range, by default the span from the
+/// lowest to the highest database ID.
+#[aoc(day5, part2, Coverage)]
+fn part2_coverage(input: &Day5Input) -> String {
+    let lo = input.ingredients_database.iter().map(|r| r.0).min();
+    let hi = input.ingredients_database.iter().map(|r| r.1).max();
+    match input.window.or(lo.zip(hi)) {
+        Some(window) => format!(
+            "\n{}",
+            Coverage::new(&input.ingredients_database, window)
+                .summary(&input.available_ingredients)
+        ),
+        None => "empty database".to_string(),
+    }
+}
+
+impl Day5Input {
+    /// Writes the database back out in the input format, with the ranges
+    /// merged, sorted and adjacent ones coalesced.
+    fn normalised(&self) -> String {
+        let mut out = String::new();
+        for (start, end) in RangeSet::from_ranges(self.ingredients_database.iter().copied()).iter()
+        {
+            out += &format!("{}-{}\n", start, end);
+        }
+        out += "\n";
+        for id in &self.available_ingredients {
+            out += &format!("{}\n", id);
+        }
+        out
+    }
+
+    /// Marks the database entries that can be dropped without changing the
+    /// set of fresh IDs.
+    ///
+    /// Each merged range is covered greedily: starting from its first ID,
+    /// keep the entry that reaches furthest, then continue from just past
+    /// its end. This keeps the fewest entries possible; when several reach
+    /// equally far, the one that comes first in the input is kept.
+    fn redundant(&self) -> Vec<bool> {
+        let database = &self.ingredients_database;
+        let mut order: Vec<usize> = (0..database.len()).collect();
+        order.sort_by_key(|&i| (database[i].0, i));
+
+        let mut redundant = vec![true; database.len()];
+        let mut next = order.iter().peekable();
+
+        while let Some(&&first) = next.peek() {
+            // last ID covered so far; i128 so the first step and u64::MAX don't overflow
+            let mut covered = database[first].0 as i128 - 1;
+            loop {
+                let mut best: Option<usize> = None;
+                while let Some(&&i) = next.peek() {
+                    if database[i].0 as i128 > covered + 1 {
+                        break;
+                    }
+                    // ties go to the earlier line, whatever its start
+                    if best
+                        .is_none_or(|b| (database[i].1, Reverse(i)) > (database[b].1, Reverse(b)))
+                    {
+                        best = Some(i);
+                    }
+                    next.next();
+                }
+
+                match best {
+                    Some(b) if database[b].1 as i128 > covered => {
+                        redundant[b] = false;
+                        covered = database[b].1 as i128;
+                    }
+                    _ => break,
+                }
             }
-            acc
-        },
-    );
+        }
+
+        redundant
+    }
+
+    /// Lists every database line, prefixing the redundant ones with `-`.
+    fn redundancy_diff(&self) -> String {
+        self.ingredients_database
+            .iter()
+            .zip(&self.database_lines)
+            .zip(self.redundant())
+            .map(|((&(start, end), line), redundant)| {
+                let marker = if redundant { '-' } else { ' ' };
+                format!("{} {:>4}  {}-{}\n", marker, line, start, end)
+            })
+            .collect()
+    }
+}
+
+#[aoc(day5, part2, Normalise)]
+fn part2_normalise(input: &Day5Input) -> String {
+    format!("\n{}", input.normalised())
+}
+
+#[aoc(day5, part2, Redundant)]
+fn part2_redundant(input: &Day5Input) -> String {
+    format!("\n{}", input.redundancy_diff())
+}
+
+/// One operation in an inventory command stream.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Command {
+    /// `+10-20`: every ID in the range becomes fresh.
+    Insert(u64, u64),
+    /// `-15-16`: every ID in the range stops being fresh.
+    Remove(u64, u64),
+    /// `?17`: is the ID fresh right now?
+    Query(u64),
+    /// `count`: how many IDs are fresh right now?
+    Count,
+}
+
+/// Output of a `Query` or `Count` command.
+#[derive(Debug, PartialEq, Eq)]
+enum Response {
+    Fresh(bool),
+    Count(u128),
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Fresh(true) => write!(f, "fresh"),
+            Response::Fresh(false) => write!(f, "spoiled"),
+            Response::Count(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl Day5Input {
+    /// The static database expressed as a stream: insert every range, then
+    /// query every available ingredient.
+    fn to_commands(&self) -> Vec<Command> {
+        self.ingredients_database
+            .iter()
+            .map(|&(start, end)| Command::Insert(start, end))
+            .chain(
+                self.available_ingredients
+                    .iter()
+                    .map(|&id| Command::Query(id)),
+            )
+            .collect()
+    }
+}
+
+/// Parses a command stream, one command per line.
+///
+/// Input in the regular ranges-then-ingredients format is accepted too and
+/// converted with [`Day5Input::to_commands`]. A file is treated as a stream
+/// if any line starts with `+`, `-` or `?`, or is `count`.
+fn parse_commands(input: &str) -> Result<Vec<Command>, ParseError> {
+    let is_stream = input
+        .lines()
+        .filter_map(strip_comment)
+        .any(|line| line.starts_with(['+', '-', '?']) || line == "count");
+    if !is_stream {
+        return Ok(parse(input)?.to_commands());
+    }
+
+    let options = ParseOptions::default();
+    let mut commands = Vec::new();
+
+    for (n, raw) in input.lines().enumerate() {
+        let line = match strip_comment(raw) {
+            Some("") | None => continue,
+            Some(line) => line,
+        };
+
+        let command = if line == "count" {
+            Ok(Command::Count)
+        } else if let Some(spec) = line.strip_prefix('+') {
+            parse_range(spec, options).map(|(start, end)| Command::Insert(start, end))
+        } else if let Some(spec) = line.strip_prefix('-') {
+            parse_range(spec, options).map(|(start, end)| Command::Remove(start, end))
+        } else if let Some(id) = line.strip_prefix('?') {
+            id.trim()
+                .parse()
+                .map(Command::Query)
+                .context("Failed to parse ingredient")
+        } else {
+            Err(anyhow!("Expected '+', '-', '?' or 'count'"))
+        };
+
+        commands.push(command.map_err(|e| {
+            ParseError::new(5, n + 1, format!("invalid command '{}'", line))
+                .with_column(column_of(raw, line))
+                .with_source(e)
+        })?);
+    }
+
+    Ok(commands)
+}
+
+/// Applies `commands` in order, returning a response for every `Query` and
+/// `Count` along with the final database.
+fn run_commands(commands: &[Command]) -> (Vec<Response>, DynamicRangeSet<u64>) {
+    let mut fresh = DynamicRangeSet::new();
+    let mut responses = Vec::new();
+
+    for command in commands {
+        match *command {
+            Command::Insert(start, end) => fresh.insert(start, end),
+            Command::Remove(start, end) => fresh.remove(start, end),
+            Command::Query(id) => responses.push(Response::Fresh(fresh.contains(id))),
+            Command::Count => responses.push(Response::Count(fresh.len())),
+        }
+    }
 
-    acc.result.push(acc.current);
+    (responses, fresh)
+}
 
-    // println!("acc: {:?}", acc);
+#[aoc_generator(day5, part1, Stream)]
+fn parse_stream_part1(input: &str) -> Result<Vec<Command>, ParseError> {
+    parse_commands(input)
+}
 
-    acc.result
+#[aoc_generator(day5, part2, Stream)]
+fn parse_stream_part2(input: &str) -> Result<Vec<Command>, ParseError> {
+    parse_commands(input)
+}
+
+/// Number of queries answered fresh; equal to `part1` for regular input.
+#[aoc(day5, part1, Stream)]
+fn part1_stream(commands: &[Command]) -> usize {
+    run_commands(commands)
+        .0
         .iter()
-        .map(|(start, end)| end - start + 1)
-        .sum::<u64>()
-        .to_string()
+        .filter(|&r| *r == Response::Fresh(true))
+        .count()
+}
+
+/// Number of fresh IDs once the stream ends; equal to `part2` for regular input.
+#[aoc(day5, part2, Stream)]
+fn part2_stream(commands: &[Command]) -> u128 {
+    run_commands(commands).1.len()
+}
+
+pub(crate) struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+    type Input = Day5Input;
+
+    fn parse(input: &str) -> Result<Day5Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Day5Input) -> anyhow::Result<Answer> {
+        Ok(part1(input).into())
+    }
+
+    fn part2(input: &Day5Input) -> anyhow::Result<Answer> {
+        Ok(part2(input).into())
+    }
 }
 
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 5,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 1,
+        name: Some("Report"),
+        run: |input| Ok(part1_report(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 1,
+        name: Some("ReportJson"),
+        run: |input| Ok(part1_report_json(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 1,
+        name: Some("ReportCsv"),
+        run: |input| Ok(part1_report_csv(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 1,
+        name: Some("Stream"),
+        run: |input| Ok(part1_stream(&parse_stream_part1(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        name: Some("Coverage"),
+        run: |input| Ok(part2_coverage(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        name: Some("Normalise"),
+        run: |input| Ok(part2_normalise(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        name: Some("Redundant"),
+        run: |input| Ok(part2_redundant(&parse(input)?).into()),
+    },
+    Solver {
+        day: 5,
+        part: 2,
+        name: Some("Stream"),
+        run: |input| Ok(part2_stream(&parse_stream_part2(input)?).into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "3-5
-    10-14
-    16-20
-    12-18
-
-    1
-    5
-    8
-    11
-    17
-    32";
+    const EXAMPLE_INPUT: &str = include_str!("../examples/day5/example.txt");
 
     #[test]
     fn part1_example() {
-        assert_eq!(part1(&parse(EXAMPLE_INPUT)), "3");
+        assert_eq!(part1(&parse(EXAMPLE_INPUT).unwrap()), 3);
     }
 
     #[test]
     fn part2_tests() {
         struct TestCase<'a> {
             input: &'a str,
-            want: &'a str,
+            want: u128,
         }
 
         let test_cases = vec![
             TestCase {
                 input: "3-5",
-                want: "3",
+                want: 3,
             },
             TestCase {
                 input: "3-5
             4-6",
-                want: "4",
+                want: 4,
             },
             TestCase {
                 input: "3-5
                 8-10",
-                want: "6",
+                want: 6,
+            },
+            TestCase {
+                input: "3-5
+                6-8",
+                want: 6,
             },
         ];
 
         for tc in test_cases {
-            assert_eq!(part2(&parse(tc.input)), tc.want);
+            assert_eq!(part2(&parse(tc.input).unwrap()), tc.want);
         }
     }
 
+    #[test]
+    fn empty_database() {
+        assert_eq!(part1(&parse("\n1\n2").unwrap()), 0);
+        assert_eq!(part2(&parse("\n1\n2").unwrap()), 0);
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                "3-5
+            part2(
+                &parse(
+                    "3-5
         10-14
         16-20
         12-18"
-            )),
-            "14"
+                )
+                .unwrap()
+            ),
+            14
+        );
+    }
+
+    #[test]
+    fn part2_full_domain() {
+        assert_eq!(
+            part2(&parse("0-18446744073709551615").unwrap()),
+            18446744073709551616
+        );
+        assert_eq!(
+            part2(&parse("0-9\n10-18446744073709551615\n5-5").unwrap()),
+            18446744073709551616
         );
+        assert_eq!(
+            part2(&parse("18446744073709551615-18446744073709551615").unwrap()),
+            1
+        );
+        assert_eq!(part2(&parse("0-0").unwrap()), 1);
+    }
+
+    #[test]
+    fn part1_domain_edges() {
+        let input = parse("0-0\n18446744073709551615-18446744073709551615\n\n0\n1\n18446744073709551614\n18446744073709551615").unwrap();
+        assert_eq!(part1(&input), 2);
+    }
+
+    #[test]
+    fn reversed_range_rejected() {
+        let err = parse("10-5").err().unwrap();
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Day 5, line 1, column 1: invalid range '10-5': Reversed range 10-5: start 10 is after end 5"
+        );
+    }
+
+    #[test]
+    fn reversed_range_normalised() {
+        let options = ParseOptions {
+            normalise_reversed: true,
+        };
+        let input = parse_with("10-5\n3-4", options).unwrap();
+        assert_eq!(input.ingredients_database, vec![(5, 10), (3, 4)]);
+        assert_eq!(part2(&input), 8);
+    }
+
+    #[test]
+    fn overflowing_id_rejected() {
+        assert!(parse("0-18446744073709551616").is_err());
+    }
+
+    #[test]
+    fn parse_range_syntax() {
+        let options = ParseOptions::default();
+        let cases = [
+            ("3-5", (3, 5)),
+            ("42", (42, 42)),
+            ("100-", (100, u64::MAX)),
+            ("[3,5)", (3, 4)),
+            ("[3,5]", (3, 5)),
+            ("(3,5]", (4, 5)),
+            ("(3,5)", (4, 4)),
+            ("[0, 18446744073709551615]", (0, u64::MAX)),
+            ("100+20", (100, 119)),
+            ("100+1", (100, 100)),
+            ("18446744073709551615+1", (u64::MAX, u64::MAX)),
+        ];
+        for (spec, want) in cases {
+            assert_eq!(parse_range(spec, options).unwrap(), want, "{}", spec);
+        }
+    }
+
+    #[test]
+    fn parse_range_errors() {
+        let options = ParseOptions::default();
+        for spec in [
+            "[3,3)",
+            "(3,4)",
+            "100+0",
+            "18446744073709551615+2",
+            "[3,5",
+            "3,5]",
+            "a-5",
+            "-5",
+            "3-5-7",
+        ] {
+            assert!(parse_range(spec, options).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn parse_extended_database() {
+        let input = parse(
+            "# fresh ranges
+            3-5
+            10+5 # same as 10-14
+            [16,21)
+            12-18
+            42
+            100-
+
+            # available ingredients
+            1
+            5
+            8 # not fresh
+            11
+            17
+            32
+            42
+            18446744073709551615",
+        )
+        .unwrap();
+        assert_eq!(
+            input.ingredients_database,
+            vec![
+                (3, 5),
+                (10, 14),
+                (16, 20),
+                (12, 18),
+                (42, 42),
+                (100, u64::MAX)
+            ]
+        );
+        assert_eq!(
+            input.available_ingredients,
+            vec![1, 5, 8, 11, 17, 32, 42, u64::MAX]
+        );
+        assert_eq!(part1(&input), 5);
+    }
+
+    #[test]
+    fn parse_errors_report_line() {
+        let err = parse("3-5\n\n1\nx").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 4, column 1: invalid ingredient 'x'"
+        );
+
+        let err = parse("# header\n3-5\n[4,2]").err().unwrap();
+        assert!(err
+            .to_string()
+            .starts_with("Day 5, line 3, column 1: invalid range '[4,2]'"));
+
+        let err = parse("3-5\n  7-x # spoiled").err().unwrap();
+        assert_eq!((err.day, err.line, err.column), (5, 2, Some(3)));
+        assert_eq!(err.message(), "invalid range '7-x'");
+    }
+
+    #[test]
+    fn freshness_report() {
+        let input = parse(EXAMPLE_INPUT).unwrap();
+        let report = FreshnessReport::new(&input);
+
+        assert_eq!(report.fresh_count(), part1(&input));
+        assert_eq!(
+            report.ingredients[4],
+            IngredientStatus {
+                id: 17,
+                matched_lines: vec![3, 4]
+            }
+        );
+        assert_eq!(report.ingredients.len(), 6);
+        assert!(report.unmatched_ranges.is_empty());
+    }
+
+    #[test]
+    fn freshness_report_formats() {
+        let input = parse("3-5\n10-14\n20-30\n\n5\n8\n12").unwrap();
+        let report = FreshnessReport::new(&input);
+
+        assert_eq!(
+            report.to_table(),
+            "ID  FRESH  LINES
+5   yes    1
+8   no     -
+12  yes    2
+2 of 3 ingredients fresh
+
+1 ranges matched no ingredient
+  line 3: 20-30
+"
+        );
+        assert_eq!(
+            report.to_json(),
+            r#"{"ingredients":[{"id":5,"fresh":true,"lines":[1]},{"id":8,"fresh":false,"lines":[]},{"id":12,"fresh":true,"lines":[2]}],"unmatched_ranges":[{"line":3,"start":20,"end":30}]}"#
+        );
+        assert_eq!(
+            report.to_csv(),
+            "id,fresh,lines
+5,true,1
+8,false,
+12,true,2
+
+line,start,end
+3,20,30
+"
+        );
+    }
+
+    #[test]
+    fn command_stream() {
+        let commands = parse_commands(
+            "+10-20
+            -15-16
+            ?17
+            ?15
+            count # 9 IDs
+            +[15,17)
+            ?15
+            count",
+        )
+        .unwrap();
+        assert_eq!(commands[0], Command::Insert(10, 20));
+        assert_eq!(commands[1], Command::Remove(15, 16));
+
+        let (responses, fresh) = run_commands(&commands);
+        assert_eq!(
+            responses.iter().map(|r| r.to_string()).collect::<Vec<_>>(),
+            vec!["fresh", "spoiled", "9", "fresh", "11"]
+        );
+        assert_eq!(fresh.iter().collect::<Vec<_>>(), vec![(10, 20)]);
+        assert_eq!(part1_stream(&commands), 2);
+        assert_eq!(part2_stream(&commands), 11);
+    }
+
+    #[test]
+    fn command_stream_regular_format() {
+        let commands = parse_commands(EXAMPLE_INPUT).unwrap();
+        let input = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(commands, input.to_commands());
+        assert_eq!(part1_stream(&commands), part1(&input));
+        assert_eq!(part2_stream(&commands), part2(&input));
+    }
+
+    #[test]
+    fn command_stream_errors() {
+        let err = parse_commands("+1-5\n?x").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 2, column 1: invalid command '?x'"
+        );
+
+        let err = parse_commands("count\nadd 1-5").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 2, column 1: invalid command 'add 1-5'"
+        );
+    }
+
+    #[test]
+    fn coverage_example() {
+        let input = parse(EXAMPLE_INPUT).unwrap();
+        let coverage = Coverage::new(&input.ingredients_database, (3, 20));
+
+        assert_eq!(
+            coverage.segments,
+            vec![
+                (3, 5, 1),
+                (6, 9, 0),
+                (10, 11, 1),
+                (12, 14, 2),
+                (15, 15, 1),
+                (16, 18, 2),
+                (19, 20, 1)
+            ]
+        );
+        assert_eq!(coverage.gaps(), vec![(6, 9)]);
+        assert_eq!(coverage.largest_gap(), Some((6, 9)));
+        assert_eq!(coverage.max_depth(), 2);
+        assert_eq!(coverage.depth_at(13), 2);
+        assert_eq!(coverage.depth_at(7), 0);
+        assert_eq!(coverage.depth_at(100), 0);
+        assert_eq!(coverage.covered(), part2(&input));
+        // lengths 3, 5, 5 and 7
+        assert_eq!(coverage.length_histogram, vec![(1, 2), (3, 4)]);
+    }
+
+    #[test]
+    fn coverage_window() {
+        let database = [(3, 5), (10, 14), (16, 20), (12, 18)];
+        let coverage = Coverage::new(&database, (0, 30));
+        assert_eq!(coverage.gaps(), vec![(0, 2), (6, 9), (21, 30)]);
+        assert_eq!(coverage.largest_gap(), Some((21, 30)));
+
+        let coverage = Coverage::new(&database, (13, 17));
+        assert_eq!(
+            coverage.segments,
+            vec![(13, 14, 2), (15, 15, 1), (16, 17, 2)]
+        );
+        assert!(coverage.gaps().is_empty());
+        assert_eq!(coverage.largest_gap(), None);
+
+        let coverage = Coverage::new(&[], (5, 9));
+        assert_eq!(coverage.gaps(), vec![(5, 9)]);
+        assert_eq!(coverage.max_depth(), 0);
+    }
+
+    #[test]
+    fn coverage_full_domain() {
+        let coverage = Coverage::new(&[(0, u64::MAX), (u64::MAX, u64::MAX)], (0, u64::MAX));
+        assert_eq!(
+            coverage.segments,
+            vec![(0, u64::MAX - 1, 1), (u64::MAX, u64::MAX, 2)]
+        );
+        assert_eq!(coverage.covered(), 1 << 64);
+        assert_eq!(coverage.length_histogram, vec![(1, 1), (1, 1 << 64)]);
+    }
+
+    #[test]
+    fn coverage_summary() {
+        let input = parse("3-5\n10-14\n12-18\n\n8\n13").unwrap();
+        assert_eq!(
+            part2_coverage(&input),
+            "
+covered: 12 of 16 IDs in 3-18
+gaps: 1
+  6-9
+largest gap: 6-9 (4 IDs)
+max overlap depth: 2
+  12-14
+depth at ingredient:
+  8: 0
+  13: 2
+range lengths:
+  2-3: 1
+  4-7: 2
+"
+        );
+
+        let input = parse("# window: 0-30\n3-5\n10-14\n12-18\n\n8").unwrap();
+        assert!(part2_coverage(&input).starts_with("\ncovered: 12 of 31 IDs in 0-30\ngaps: 3\n"));
+        let input = parse("# window: [5,10)\n\n").unwrap();
+        assert_eq!(input.window, Some((5, 9)));
+        assert!(part2_coverage(&input).starts_with("\ncovered: 0 of 5 IDs in 5-9\n"));
+
+        let err = parse("3-5\n  # window: 9-x").err().unwrap();
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Day 5, line 2, column 13: invalid window '9-x': Failed to parse ID 'x': invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn normalised_round_trip() {
+        let input = parse("16-20\n3-5\n12-18\n6\n10-14\n100-\n\n1\n5").unwrap();
+        let normalised = input.normalised();
+        assert_eq!(normalised, "3-6\n10-20\n100-18446744073709551615\n\n1\n5\n");
+
+        let reparsed = parse(&normalised).unwrap();
+        assert_eq!(part1(&reparsed), part1(&input));
+        assert_eq!(part2(&reparsed), part2(&input));
+        assert_eq!(reparsed.normalised(), normalised);
+    }
+
+    #[test]
+    fn redundant_lines() {
+        let input = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(input.redundant(), vec![false; 4]);
+
+        let input = parse(
+            "# header
+            10-20
+            12-14
+            1-5
+            15-25
+            10-20
+            18-30",
+        )
+        .unwrap();
+        assert_eq!(
+            input.redundant(),
+            vec![false, true, false, true, true, false]
+        );
+        assert_eq!(
+            input.redundancy_diff(),
+            "     2  10-20
+-    3  12-14
+     4  1-5
+-    5  15-25
+-    6  10-20
+     7  18-30
+"
+        );
+
+        let kept = Day5Input {
+            ingredients_database: input
+                .ingredients_database
+                .iter()
+                .zip(input.redundant())
+                .filter(|(_, redundant)| !redundant)
+                .map(|(&range, _)| range)
+                .collect(),
+            database_lines: vec![],
+            available_ingredients: vec![],
+            window: None,
+        };
+        assert_eq!(part2(&kept), part2(&input));
+    }
+
+    #[test]
+    fn redundant_adjacent_and_domain_edges() {
+        let input = parse("0-4\n5-9\n0-9\n18446744073709551615\n18446744073709551610-").unwrap();
+        assert_eq!(input.redundant(), vec![true, true, false, true, false]);
+    }
+
+    #[test]
+    fn redundant_ties_keep_first_line() {
+        // 4-10 and 3-10 both reach 10 from 5; 4-10 comes first
+        let input = parse("1-5\n4-10\n3-10").unwrap();
+        assert_eq!(input.redundant(), vec![false, false, true]);
+        let input = parse("1-5\n3-10\n4-10").unwrap();
+        assert_eq!(input.redundant(), vec![false, false, true]);
+        let input = parse("2-3\n2-3").unwrap();
+        assert_eq!(input.redundant(), vec![false, true]);
     }
 }
@@ -1,130 +1,2018 @@
+use crate::error::{column_of, ParseError};
+use crate::registry::Solver;
+use crate::solution::{join, Answer, Solution};
+use anyhow::{anyhow, bail, Context};
 use aoc_runner_derive::{aoc, aoc_generator};
-#[aoc_generator(day6)]
-fn parse(input: &str) -> Vec<Problem> {
-    let mut problems = Vec::<Problem>::new();
-    let mut vertical = Vec::<String>::new();
-
-    input.lines().for_each(|line| {
-        if line.contains("*") || line.contains("+") {
-            // ops
-            line.trim()
-                .split_ascii_whitespace()
-                .enumerate()
-                .for_each(|(i, op)| match op {
-                    "+" => problems[i].op = Op::Plus,
-                    "*" => problems[i].op = Op::Mul,
-                    _ => panic!("unknown operator"),
-                });
+use num_bigint::BigInt;
+use num_traits::Zero;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Tab stops in worksheets are every `TAB_WIDTH` columns.
+const TAB_WIDTH: usize = 8;
+
+/// Largest power the exact mode computes, in bits. A few digits of input
+/// could otherwise ask for one that takes hours.
+const MAX_EXACT_BITS: u64 = 1 << 20;
+
+/// Expands tabs to the next tab stop so columns line up as they appear.
+fn expand_tabs(line: &str) -> Vec<char> {
+    let mut row = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\t' {
+            row.resize((row.len() / TAB_WIDTH + 1) * TAB_WIDTH, ' ');
         } else {
-            // init verticals
-            if vertical.is_empty() {
-                vertical.resize(line.len(), "".to_string());
+            row.push(c);
+        }
+    }
+    row
+}
+
+/// Parses every worksheet in the input and returns all their problems.
+///
+/// References are renumbered to point into the combined list. Each problem
+/// keeps the readings of its worksheet for parts 1 and 2, so a worksheet can
+/// set at most two.
+#[aoc_generator(day6)]
+fn parse(input: &str) -> Result<Vec<Problem>, ParseError> {
+    let mut problems = Vec::new();
+    for worksheet in parse_worksheets(input)? {
+        if worksheet.readings.len() > 2 {
+            return Err(ParseError::new(
+                6,
+                worksheet.line,
+                format!(
+                    "worksheet has {} readings, parts 1 and 2 take at most 2",
+                    worksheet.readings.len()
+                ),
+            ));
+        }
+        let offset = problems.len();
+        problems.extend(worksheet.problems.into_iter().map(|mut problem| {
+            for reference in &mut problem.refs {
+                reference.problem += offset;
+            }
+            problem
+        }));
+    }
+    Ok(problems)
+}
+
+/// Parses worksheets separated by blank lines.
+fn parse_worksheets(input: &str) -> Result<Vec<Worksheet>, ParseError> {
+    let lines: Vec<(usize, &str)> = input
+        .lines()
+        .enumerate()
+        .map(|(n, line)| (n + 1, line))
+        .collect();
+
+    lines
+        .split(|(_, line)| line.trim().is_empty())
+        .filter(|lines| !lines.is_empty())
+        .map(parse_worksheet)
+        .collect()
+}
+
+/// Parses a worksheet laid out in column blocks.
+///
+/// Problems are blocks of columns separated by columns that are blank on
+/// every line. Lines may be ragged: missing columns count as blank. One
+/// line, anywhere in the worksheet, holds the operators; it is the line
+/// that does not start with a number. In every other line each block holds
+/// at most one number, or a reference such as `=P3` to the result of the
+/// worksheet's third problem.
+///
+/// Lines starting with `#` are comments, except `# read: <options>` which
+/// adds a [`Reading`] to answer the worksheet with.
+fn parse_worksheet(lines: &[(usize, &str)]) -> Result<Worksheet, ParseError> {
+    let mut readings = Vec::new();
+    for &(n, line) in lines {
+        if let Some(comment) = line.trim().strip_prefix('#') {
+            if let Some(options) = comment.trim().strip_prefix("read:") {
+                readings.push(Reading::parse(options).map_err(|e| {
+                    ParseError::new(6, n, e.to_string()).with_column(column_of(line, options))
+                })?);
             }
+        }
+    }
+    if readings.is_empty() {
+        readings = vec![Reading::ROWS, Reading::COLUMNS];
+    }
+    let parts = [readings[0], *readings.get(1).unwrap_or(&readings[0])];
+
+    let rows: Vec<(usize, Vec<char>)> = lines
+        .iter()
+        .filter(|(_, line)| !line.trim().starts_with('#'))
+        .map(|&(n, line)| (n, expand_tabs(line)))
+        .collect();
 
-            for (i, c) in line.chars().enumerate() {
-                vertical[i].push(c);
+    let mut op_line = None;
+    for (n, row) in &rows {
+        let first: String = row
+            .iter()
+            .skip_while(|c| c.is_whitespace())
+            .take_while(|c| !c.is_whitespace())
+            .collect();
+        if !first.bytes().all(|b| b.is_ascii_digit()) && !first.starts_with('=') {
+            if let Some(first_line) = op_line {
+                return Err(ParseError::new(
+                    6,
+                    *n,
+                    format!("second operator line, the first is line {}", first_line),
+                ));
             }
+            op_line = Some(*n);
+        }
+    }
+    let Some(op_line) = op_line else {
+        if rows.is_empty() {
+            return Ok(Worksheet {
+                line: lines[0].0,
+                problems: vec![],
+                readings,
+            });
+        }
+        return Err(ParseError::new(6, lines[0].0, "no operator line found"));
+    };
 
-            line.split_ascii_whitespace()
-                .enumerate()
-                .for_each(|(i, number)| {
-                    while problems.len() < i + 1 {
-                        problems.push(Problem {
-                            args: vec![],
-                            args_vertical: vec![],
-                            op: Op::Mul,
-                        })
+    // split into blocks of columns at columns that are blank on every line
+    let width = rows.iter().map(|(_, row)| row.len()).max().unwrap_or(0);
+    let blank = |column: usize| {
+        rows.iter()
+            .all(|(_, row)| row.get(column).is_none_or(|c| c.is_whitespace()))
+    };
+    let mut blocks = Vec::new();
+    let mut start = None;
+    for column in 0..=width {
+        if column < width && !blank(column) {
+            start.get_or_insert(column);
+        } else if let Some(start) = start.take() {
+            blocks.push(start..column);
+        }
+    }
+
+    let count = blocks.len();
+    let problems = blocks
+        .into_iter()
+        .map(|block| {
+            let mut op = None;
+            let mut args = Vec::new();
+            let mut refs = Vec::new();
+            let mut digits = Vec::new();
+
+            for (n, row) in &rows {
+                let cell: String = block
+                    .clone()
+                    .map(|c| row.get(c).copied().unwrap_or(' '))
+                    .collect();
+                let value = cell.trim();
+                if *n != op_line {
+                    // references have no digits for the other readings
+                    if value.starts_with('=') {
+                        digits.push(vec![' '; cell.chars().count()]);
+                    } else {
+                        digits.push(cell.chars().collect());
                     }
+                }
+                if value.is_empty() {
+                    continue;
+                }
+                let column =
+                    block.start + cell.chars().take_while(|c| c.is_whitespace()).count() + 1;
 
-                    problems[i].args.push(number.parse::<u64>().unwrap());
-                });
+                let error = |message: String| ParseError::new(6, *n, message).with_column(column);
+
+                if *n == op_line {
+                    op = Some(
+                        Op::from_symbol(value)
+                            .ok_or_else(|| error(format!("unknown operator '{}'", value)))?,
+                    );
+                } else if value.starts_with('=') {
+                    let problem = parse_reference(value)
+                        .ok_or_else(|| error(format!("invalid reference '{}'", value)))?;
+                    if problem >= count {
+                        return Err(error(format!(
+                            "'{}' but the worksheet has {} problems",
+                            value, count
+                        )));
+                    }
+                    refs.push(Reference {
+                        position: args.len() + refs.len(),
+                        problem,
+                    });
+                } else {
+                    args.push(value.parse::<u64>().map_err(|e| {
+                        error(format!("'{}' is not a single number", value)).with_source(e)
+                    })?);
+                }
+            }
+
+            let error =
+                |message: String| ParseError::new(6, op_line, message).with_column(block.start + 1);
+            let op = op.ok_or_else(|| {
+                error(format!(
+                    "problem in columns {}-{} has no operator",
+                    block.start + 1,
+                    block.end
+                ))
+            })?;
+            if args.is_empty() && refs.is_empty() {
+                return Err(error(format!(
+                    "operator '{}' has no numbers in its columns",
+                    op
+                )));
+            }
+
+            let mut problem = Problem {
+                args,
+                args_vertical: vec![],
+                op,
+                digits,
+                refs,
+                readings: parts,
+            };
+            problem.args_vertical = problem.read(Reading::COLUMNS).map_err(|e| {
+                error(format!("columns {}-{}", block.start + 1, block.end)).with_source(e)
+            })?;
+            Ok(problem)
+        })
+        .collect::<Result<_, ParseError>>()?;
+
+    Ok(Worksheet {
+        line: lines[0].0,
+        problems,
+        readings,
+    })
+}
+
+/// Which way a problem's numbers are read out of its block of digits.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Reading {
+    /// Every column is a number, instead of every row.
+    by_column: bool,
+    /// Rows are read right to left; by column, the rightmost column is the
+    /// first number.
+    right_to_left: bool,
+    /// The bottom row is the first number; by column, columns are read
+    /// bottom to top.
+    bottom_to_top: bool,
+}
+
+impl Reading {
+    /// Each row is a number, first row first: `args`.
+    const ROWS: Reading = Reading {
+        by_column: false,
+        right_to_left: false,
+        bottom_to_top: false,
+    };
+
+    /// Each column is a number read top to bottom, leftmost first: `args_vertical`.
+    const COLUMNS: Reading = Reading {
+        by_column: true,
+        right_to_left: false,
+        bottom_to_top: false,
+    };
+
+    /// Parses space- or comma-separated options: `rows` or `columns`,
+    /// `right-to-left` and `bottom-to-top`.
+    fn parse(options: &str) -> anyhow::Result<Reading> {
+        let mut reading = Reading::ROWS;
+        for option in options.split([' ', ',']).filter(|o| !o.is_empty()) {
+            match option {
+                "rows" => reading.by_column = false,
+                "columns" => reading.by_column = true,
+                "right-to-left" => reading.right_to_left = true,
+                "bottom-to-top" => reading.bottom_to_top = true,
+                _ => bail!("Unknown reading option '{}'", option),
+            }
         }
-    });
+        Ok(reading)
+    }
+}
 
-    vertical.iter().fold(0, |i, number| {
-        if number.trim().is_empty() {
-            i + 1
-        } else {
-            problems[i]
-                .args_vertical
-                .push(number.trim().parse::<u64>().unwrap());
-            i
+impl fmt::Display for Reading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", if self.by_column { "columns" } else { "rows" })?;
+        if self.right_to_left {
+            write!(f, ", right-to-left")?;
+        }
+        if self.bottom_to_top {
+            write!(f, ", bottom-to-top")?;
         }
-    });
+        Ok(())
+    }
+}
 
-    problems
+/// One worksheet from an input that may hold several.
+#[derive(Debug)]
+struct Worksheet {
+    /// Line the worksheet starts on.
+    line: usize,
+    problems: Vec<Problem>,
+    /// From the `# read:` lines, or rows then columns if there are none.
+    readings: Vec<Reading>,
 }
 
 #[derive(Debug)]
-struct Problem {
+pub(crate) struct Problem {
     args: Vec<u64>,
     args_vertical: Vec<u64>,
     op: Op,
+    /// The problem's block without the operator line, one row per line,
+    /// padded with spaces to the block's width.
+    digits: Vec<Vec<char>>,
+    /// `=Pn` cells, in row order, kept out of `args` and `digits`.
+    refs: Vec<Reference>,
+    /// The readings of parts 1 and 2: the first two of the worksheet, the
+    /// only one for both, or rows then columns.
+    readings: [Reading; 2],
 }
 
-#[derive(Debug)]
+/// A `=Pn` cell: another problem's result used as an operand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Reference {
+    /// Position among the problem's row operands, counting `args` and `refs`.
+    position: usize,
+    /// Index of the referenced problem.
+    problem: usize,
+}
+
+/// Parses a `=P3` cell into the index of the problem it names.
+fn parse_reference(cell: &str) -> Option<usize> {
+    let n: usize = cell.strip_prefix("=P")?.parse().ok()?;
+    n.checked_sub(1)
+}
+
+impl Problem {
+    /// Reads the problem's numbers out of `digits`.
+    fn read(&self, reading: Reading) -> anyhow::Result<Vec<u64>> {
+        let width = self.digits.first().map_or(0, |row| row.len());
+
+        let mut numbers: Vec<String> = if reading.by_column {
+            (0..width)
+                .map(|c| {
+                    let column = self.digits.iter().map(|row| row[c]);
+                    if reading.bottom_to_top {
+                        column.rev().collect()
+                    } else {
+                        column.collect()
+                    }
+                })
+                .collect()
+        } else {
+            self.digits
+                .iter()
+                .map(|row| {
+                    if reading.right_to_left {
+                        row.iter().rev().collect()
+                    } else {
+                        row.iter().collect()
+                    }
+                })
+                .collect()
+        };
+        if reading.by_column && reading.right_to_left || !reading.by_column && reading.bottom_to_top
+        {
+            numbers.reverse();
+        }
+
+        numbers
+            .iter()
+            .map(|n| n.chars().filter(|c| !c.is_whitespace()).collect::<String>())
+            .filter(|n| !n.is_empty())
+            .map(|n| {
+                n.parse::<u64>()
+                    .with_context(|| format!("Invalid number '{}' reading {}", n, reading))
+            })
+            .collect()
+    }
+
+    /// The numbers for `reading`, borrowing the ones parsed up front for
+    /// rows and columns.
+    ///
+    /// A problem with references has no operands on its own; see
+    /// [`evaluate_expressions`].
+    fn operands(&self, reading: Reading) -> anyhow::Result<Cow<'_, [u64]>> {
+        if !self.refs.is_empty() {
+            bail!("References other problems, answer it as an expression");
+        }
+        if reading == Reading::ROWS {
+            Ok(Cow::Borrowed(&self.args))
+        } else if reading == Reading::COLUMNS {
+            Ok(Cow::Borrowed(&self.args_vertical))
+        } else {
+            self.read(reading).map(Cow::Owned)
+        }
+    }
+
+    /// The row operands with each reference replaced by its problem's entry
+    /// in `results`.
+    fn resolve(&self, results: &[u64]) -> Vec<u64> {
+        let mut args = self.args.iter();
+        let mut refs = self.refs.iter().peekable();
+        (0..self.args.len() + self.refs.len())
+            .map(|position| match refs.next_if(|r| r.position == position) {
+                Some(reference) => results[reference.problem],
+                None => *args.next().expect("args and refs cover every position"),
+            })
+            .collect()
+    }
+
+    /// The row cells as written: numbers and `=Pn` references.
+    fn cells(&self) -> Vec<String> {
+        let mut args = self.args.iter();
+        let mut refs = self.refs.iter().peekable();
+        (0..self.args.len() + self.refs.len())
+            .map(|position| match refs.next_if(|r| r.position == position) {
+                Some(reference) => format!("=P{}", reference.problem + 1),
+                None => args.next().map_or_else(String::new, u64::to_string),
+            })
+            .collect()
+    }
+}
+
+/// Which end of a problem's numbers evaluation starts from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fold {
+    /// `a op b op c` is `(a op b) op c`.
+    Left,
+    /// `a op b op c` is `a op (b op c)`.
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Op {
     Mul,
     Plus,
+    /// Subtraction, folded left: `10 - 3 - 2` is `(10 - 3) - 2`.
+    Minus,
+    /// Integer division, folded left: `100 / 5 / 2` is `(100 / 5) / 2`.
+    Div,
+    /// Exponentiation, folded right like the usual notation: `2 ^ 3 ^ 2` is `2 ^ (3 ^ 2)`.
+    Pow,
+    Min,
+    Max,
+    /// Remainder, folded left: `100 % 7 % 3` is `(100 % 7) % 3`.
+    Rem,
 }
 
-#[aoc(day6, part1)]
-fn part1(input: &Vec<Problem>) -> String {
-    // println!("{:?}", input);
+impl Op {
+    fn from_symbol(symbol: &str) -> Option<Op> {
+        match symbol {
+            "*" => Some(Op::Mul),
+            "+" => Some(Op::Plus),
+            "-" => Some(Op::Minus),
+            "/" => Some(Op::Div),
+            "^" => Some(Op::Pow),
+            "min" => Some(Op::Min),
+            "max" => Some(Op::Max),
+            "%" => Some(Op::Rem),
+            _ => None,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Mul => "*",
+            Op::Plus => "+",
+            Op::Minus => "-",
+            Op::Div => "/",
+            Op::Pow => "^",
+            Op::Min => "min",
+            Op::Max => "max",
+            Op::Rem => "%",
+        }
+    }
+
+    fn fold_order(self) -> Fold {
+        match self {
+            Op::Pow => Fold::Right,
+            _ => Fold::Left,
+        }
+    }
+
+    /// The value of a problem with no numbers.
+    ///
+    /// For `*`, `+`, `min` and `max` this is the usual identity element.
+    /// `-`, `/` and `^` only have a right identity: `a - 0`, `a / 1` and
+    /// `a ^ 1` are all `a`. `%` has none.
+    fn identity(self) -> Option<u64> {
+        match self {
+            Op::Mul | Op::Div | Op::Pow => Some(1),
+            Op::Plus | Op::Minus | Op::Max => Some(0),
+            Op::Min => Some(u64::MAX),
+            Op::Rem => None,
+        }
+    }
+
+    /// Applies the operator in `u64`, failing instead of wrapping or panicking.
+    fn checked_apply(self, a: u64, b: u64) -> Result<u64, ArithError> {
+        match self {
+            Op::Mul => a.checked_mul(b).ok_or(ArithError::Overflow),
+            Op::Plus => a.checked_add(b).ok_or(ArithError::Overflow),
+            Op::Minus => a.checked_sub(b).ok_or(ArithError::Negative),
+            Op::Div => a.checked_div(b).ok_or(ArithError::DivisionByZero),
+            Op::Pow => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_pow(b))
+                .ok_or(ArithError::Overflow),
+            Op::Min => Ok(a.min(b)),
+            Op::Max => Ok(a.max(b)),
+            Op::Rem => a.checked_rem(b).ok_or(ArithError::DivisionByZero),
+        }
+    }
+
+    /// Applies the operator exactly. Only division by zero and powers of more
+    /// than `MAX_EXACT_BITS` bits can fail.
+    fn big_apply(self, a: BigInt, b: BigInt) -> Result<BigInt, ArithError> {
+        match self {
+            Op::Mul => Ok(a * b),
+            Op::Plus => Ok(a + b),
+            Op::Minus => Ok(a - b),
+            Op::Div if b.is_zero() => Err(ArithError::DivisionByZero),
+            Op::Div => Ok(a / b),
+            Op::Pow => {
+                // a^b has more than (bits(a) - 1) * b bits
+                let b = u32::try_from(&b).map_err(|_| ArithError::Overflow)?;
+                if a.bits().saturating_sub(1).saturating_mul(u64::from(b)) > MAX_EXACT_BITS {
+                    return Err(ArithError::Overflow);
+                }
+                Ok(a.pow(b))
+            }
+            Op::Min => Ok(a.min(b)),
+            Op::Max => Ok(a.max(b)),
+            Op::Rem if b.is_zero() => Err(ArithError::DivisionByZero),
+            Op::Rem => Ok(a % b),
+        }
+    }
 
+    /// Folds `args` with `apply` in this operator's `fold_order`.
+    ///
+    /// An empty problem evaluates to the identity, or 0 for `%`.
+    fn fold<T>(
+        self,
+        args: &[u64],
+        lift: impl Fn(u64) -> T,
+        apply: impl Fn(T, T) -> Result<T, ArithError>,
+    ) -> Result<T, ArithError> {
+        match self.fold_order() {
+            Fold::Left => match args.split_first() {
+                Some((&first, rest)) => rest
+                    .iter()
+                    .try_fold(lift(first), |acc, &x| apply(acc, lift(x))),
+                None => Ok(lift(self.identity().unwrap_or(0))),
+            },
+            Fold::Right => match args.split_last() {
+                Some((&last, rest)) => rest
+                    .iter()
+                    .rev()
+                    .try_fold(lift(last), |acc, &x| apply(lift(x), acc)),
+                None => Ok(lift(self.identity().unwrap_or(0))),
+            },
+        }
+    }
+
+    fn checked_eval(self, args: &[u64]) -> Result<u64, ArithError> {
+        self.fold(args, |x| x, |a, b| self.checked_apply(a, b))
+    }
+
+    fn big_eval(self, args: &[u64]) -> Result<BigInt, ArithError> {
+        self.fold(args, BigInt::from, |a, b| self.big_apply(a, b))
+    }
+}
+
+/// Why a problem has no result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArithError {
+    /// The result doesn't fit in `u64`, or an exponent doesn't fit in `u32`.
+    /// In exact mode, a power would have more than `MAX_EXACT_BITS` bits.
+    Overflow,
+    /// A subtraction went below zero.
+    Negative,
+    DivisionByZero,
+}
+
+impl fmt::Display for ArithError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArithError::Overflow => write!(f, "overflowed u64"),
+            ArithError::Negative => write!(f, "went below zero"),
+            ArithError::DivisionByZero => write!(f, "divided by zero"),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Sums every problem's result, read as `reading` says, reporting the first
+/// problem that cannot be evaluated in `u64`.
+fn grand_total(input: &[Problem], reading: impl Fn(&Problem) -> Reading) -> anyhow::Result<u64> {
     input
         .iter()
-        .fold(0, |acc, problem| match problem.op {
-            Op::Mul => acc + problem.args.iter().fold(1, |a, b| a * *b),
-            Op::Plus => acc + problem.args.iter().fold(0, |a, b| a + *b),
+        .enumerate()
+        .try_fold(0u64, |acc, (i, problem)| {
+            let operands = problem
+                .operands(reading(problem))
+                .with_context(|| format!("Problem {}", i + 1))?;
+            let result = problem
+                .op
+                .checked_eval(&operands)
+                .map_err(|e| anyhow!("Problem {} ({}): {}", i + 1, problem.op, e))?;
+            acc.checked_add(result)
+                .ok_or_else(|| anyhow!("Grand total overflowed u64 at problem {}", i + 1))
         })
-        .to_string()
 }
 
-#[aoc(day6, part2)]
-fn part2(input: &Vec<Problem>) -> String {
+/// Sums every problem's exact result.
+fn grand_total_big(
+    input: &[Problem],
+    reading: impl Fn(&Problem) -> Reading,
+) -> anyhow::Result<BigInt> {
     input
         .iter()
-        .fold(0, |acc, problem| match problem.op {
-            Op::Mul => acc + problem.args_vertical.iter().fold(1, |a, b| a * *b),
-            Op::Plus => acc + problem.args_vertical.iter().fold(0, |a, b| a + *b),
+        .enumerate()
+        .try_fold(BigInt::zero(), |acc, (i, problem)| {
+            let operands = problem
+                .operands(reading(problem))
+                .with_context(|| format!("Problem {}", i + 1))?;
+            let result = problem
+                .op
+                .big_eval(&operands)
+                .map_err(|e| anyhow!("Problem {} ({}): {}", i + 1, problem.op, e))?;
+            Ok(acc + result)
         })
-        .to_string()
 }
 
+#[aoc(day6, part1)]
+fn part1(input: &[Problem]) -> anyhow::Result<u64> {
+    grand_total(input, |problem| problem.readings[0])
+}
+
+#[aoc(day6, part2)]
+fn part2(input: &[Problem]) -> anyhow::Result<u64> {
+    grand_total(input, |problem| problem.readings[1])
+}
+
+/// Orders problems so that each comes after every problem it references.
+///
+/// Walks the references depth first with an explicit stack, since a chain
+/// of references can be as long as the worksheet is wide.
+fn evaluation_order(problems: &[Problem]) -> anyhow::Result<Vec<usize>> {
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Mark {
+        New,
+        Active,
+        Done,
+    }
+
+    let mut marks = vec![Mark::New; problems.len()];
+    let mut order = Vec::with_capacity(problems.len());
+    // the problems being visited, each with the number of its references
+    // visited so far
+    let mut path: Vec<(usize, usize)> = vec![];
+    for start in 0..problems.len() {
+        if marks[start] != Mark::New {
+            continue;
+        }
+        marks[start] = Mark::Active;
+        path.push((start, 0));
+        while let Some(top) = path.last_mut() {
+            let (i, next) = *top;
+            let Some(reference) = problems[i].refs.get(next) else {
+                marks[i] = Mark::Done;
+                order.push(i);
+                path.pop();
+                continue;
+            };
+            top.1 += 1;
+            match marks[reference.problem] {
+                Mark::Done => {}
+                Mark::Active => {
+                    let from = path
+                        .iter()
+                        .position(|&(p, _)| p == reference.problem)
+                        .unwrap_or(0);
+                    let cycle: Vec<String> = path[from..]
+                        .iter()
+                        .map(|&(p, _)| p)
+                        .chain([reference.problem])
+                        .map(|p| format!("P{}", p + 1))
+                        .collect();
+                    bail!("Reference cycle: {}", cycle.join(" -> "));
+                }
+                Mark::New => {
+                    marks[reference.problem] = Mark::Active;
+                    path.push((reference.problem, 0));
+                }
+            }
+        }
+    }
+    Ok(order)
+}
+
+/// Evaluates every problem by rows, substituting the results of the
+/// problems it references.
+fn evaluate_expressions(problems: &[Problem]) -> anyhow::Result<Vec<u64>> {
+    let mut results = vec![0; problems.len()];
+    for i in evaluation_order(problems)? {
+        let problem = &problems[i];
+        results[i] = problem
+            .op
+            .checked_eval(&problem.resolve(&results))
+            .map_err(|e| anyhow!("Problem {} ({}): {}", i + 1, problem.op, e))?;
+    }
+    Ok(results)
+}
+
+/// Sums the results of the problems no other problem references, so each
+/// expression tree counts once. Without references this is `part1`.
+#[aoc(day6, part1, Expressions)]
+fn part1_expressions(input: &[Problem]) -> anyhow::Result<u64> {
+    let results = evaluate_expressions(input)?;
+    let mut referenced = vec![false; input.len()];
+    for reference in input.iter().flat_map(|p| &p.refs) {
+        referenced[reference.problem] = true;
+    }
+    results
+        .iter()
+        .zip(&referenced)
+        .filter(|(_, &referenced)| !referenced)
+        .try_fold(0u64, |acc, (&result, _)| acc.checked_add(result))
+        .context("Grand total overflowed u64")
+}
+
+#[aoc(day6, part1, Exact)]
+fn part1_exact(input: &[Problem]) -> anyhow::Result<BigInt> {
+    grand_total_big(input, |problem| problem.readings[0])
+}
+
+#[aoc(day6, part2, Exact)]
+fn part2_exact(input: &[Problem]) -> anyhow::Result<BigInt> {
+    grand_total_big(input, |problem| problem.readings[1])
+}
+
+#[aoc_generator(day6, part1, Reference)]
+fn parse_reference_part1(input: &str) -> Vec<Vec<char>> {
+    input.lines().map(|line| line.chars().collect()).collect()
+}
+
+#[aoc_generator(day6, part2, Reference)]
+fn parse_reference_part2(input: &str) -> Vec<Vec<char>> {
+    parse_reference_part1(input)
+}
+
+/// Solves the puzzle's own layout without [`parse`]: one worksheet, `+` and
+/// `*` on its last line, and every problem folded in `BigInt` so nothing can
+/// overflow. Cuts the problems at blank columns, then reads each one's
+/// numbers by row, or by column when `by_column`.
+fn reference_total(lines: &[Vec<char>], by_column: bool) -> anyhow::Result<BigInt> {
+    let Some((operators, rows)) = lines.split_last() else {
+        bail!("Empty worksheet");
+    };
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let cell = |line: &[char], column: usize| line.get(column).copied().unwrap_or(' ');
+
+    let mut total = BigInt::zero();
+    let mut start = 0;
+    for column in 0..=width {
+        if column < width && lines.iter().any(|line| cell(line, column) != ' ') {
+            continue;
+        }
+        let block = start..column;
+        start = column + 1;
+        if block.is_empty() {
+            continue;
+        }
+
+        let numbers: Vec<String> = if by_column {
+            block
+                .clone()
+                .map(|c| {
+                    // a shorter number in the block leaves a gap in the column
+                    rows.iter()
+                        .map(|row| cell(row, c))
+                        .filter(|&c| c != ' ')
+                        .collect()
+                })
+                .collect()
+        } else {
+            rows.iter()
+                .map(|row| block.clone().map(|c| cell(row, c)).collect())
+                .collect()
+        };
+        let mut numbers = numbers
+            .iter()
+            .map(|n| n.trim())
+            .filter(|n| !n.is_empty())
+            .map(|n| {
+                n.parse::<BigInt>()
+                    .with_context(|| format!("Columns {}-{}: '{}'", block.start + 1, block.end, n))
+            })
+            .peekable();
+        if numbers.peek().is_none() {
+            bail!("Columns {}-{} have no numbers", block.start + 1, block.end);
+        }
+
+        let operator: String = block.clone().map(|c| cell(operators, c)).collect();
+        total += match operator.trim() {
+            "+" => numbers.try_fold(BigInt::zero(), |acc, n| Ok::<_, anyhow::Error>(acc + n?))?,
+            "*" => numbers.try_fold(BigInt::from(1), |acc, n| Ok::<_, anyhow::Error>(acc * n?))?,
+            operator => bail!(
+                "Columns {}-{}: operator '{}'",
+                block.start + 1,
+                block.end,
+                operator
+            ),
+        };
+    }
+    Ok(total)
+}
+
+#[aoc(day6, part1, Reference)]
+fn part1_reference(lines: &[Vec<char>]) -> anyhow::Result<BigInt> {
+    reference_total(lines, false)
+}
+
+#[aoc(day6, part2, Reference)]
+fn part2_reference(lines: &[Vec<char>]) -> anyhow::Result<BigInt> {
+    reference_total(lines, true)
+}
+
+/// One problem evaluated both ways.
+///
+/// References only stand for a row operand, so a problem with references
+/// has no vertical reading: `result_vertical` is `None`.
+#[derive(Debug, PartialEq, Eq)]
+struct ResultRow {
+    op: Op,
+    args: Vec<u64>,
+    result: Result<u64, ArithError>,
+    args_vertical: Vec<u64>,
+    result_vertical: Option<Result<u64, ArithError>>,
+}
+
+impl ResultRow {
+    /// `results` holds the result of every problem `problem` references.
+    fn new(problem: &Problem, results: &[u64]) -> Self {
+        let args = problem.resolve(results);
+        let vertical = problem.refs.is_empty();
+        ResultRow {
+            op: problem.op,
+            result: problem.op.checked_eval(&args),
+            args,
+            args_vertical: if vertical {
+                problem.args_vertical.clone()
+            } else {
+                vec![]
+            },
+            result_vertical: vertical.then(|| problem.op.checked_eval(&problem.args_vertical)),
+        }
+    }
+
+    fn differs(&self) -> bool {
+        self.result_vertical.is_some_and(|r| r != self.result)
+    }
+}
+
+/// Every problem's operands, operator and result under the horizontal and
+/// vertical readings side by side.
+#[derive(Debug, PartialEq, Eq)]
+struct ResultTable {
+    rows: Vec<ResultRow>,
+}
+
+/// Formats a result, or the reason there is none; empty without a reading.
+fn result_text(result: Option<&Result<u64, ArithError>>) -> String {
+    match result {
+        Some(Ok(n)) => n.to_string(),
+        Some(Err(e)) => e.to_string(),
+        None => String::new(),
+    }
+}
+
+impl ResultTable {
+    /// Evaluates the problems in the order `evaluate_expressions` does, so
+    /// references show the result they stand for.
+    ///
+    /// A problem referencing one without a result has no operands to show,
+    /// so that fails the whole table.
+    fn new(problems: &[Problem]) -> anyhow::Result<Self> {
+        let mut rows: Vec<Option<ResultRow>> = problems.iter().map(|_| None).collect();
+        let mut results = vec![0; problems.len()];
+        for i in evaluation_order(problems)? {
+            let problem = &problems[i];
+            for reference in &problem.refs {
+                if let Some(Err(e)) = rows[reference.problem].as_ref().map(|row| &row.result) {
+                    bail!(
+                        "Problem {} references problem {}, which has no result: {}",
+                        i + 1,
+                        reference.problem + 1,
+                        e
+                    );
+                }
+            }
+            let row = ResultRow::new(problem, &results);
+            results[i] = row.result.unwrap_or_default();
+            rows[i] = Some(row);
+        }
+        Ok(ResultTable {
+            rows: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Renders aligned text columns; problems whose readings disagree are
+    /// marked with `*` in the last column.
+    fn to_table(&self) -> String {
+        let mut cells = vec![[
+            "#".to_string(),
+            "OP".to_string(),
+            "HORIZONTAL".to_string(),
+            "RESULT".to_string(),
+            "VERTICAL".to_string(),
+            "RESULT".to_string(),
+            "DIFFERS".to_string(),
+        ]];
+        for (i, row) in self.rows.iter().enumerate() {
+            cells.push([
+                (i + 1).to_string(),
+                row.op.to_string(),
+                join(&row.args, " "),
+                result_text(Some(&row.result)),
+                join(&row.args_vertical, " "),
+                result_text(row.result_vertical.as_ref()),
+                if row.differs() { "*" } else { "" }.to_string(),
+            ]);
+        }
+
+        let mut widths = [0; 7];
+        for row in &cells {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+
+        let mut out = String::new();
+        for row in &cells {
+            let line: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{:<width$}", cell))
+                .collect();
+            out += line.join("  ").trim_end();
+            out += "\n";
+        }
+        out += &format!(
+            "{} of {} problems differ\n",
+            self.rows.iter().filter(|r| r.differs()).count(),
+            self.rows.len()
+        );
+        out
+    }
+
+    fn to_json(&self) -> String {
+        let reading = |args: &[u64], result: Option<&Result<u64, ArithError>>| match result {
+            Some(Ok(n)) => format!(r#"{{"args":[{}],"result":{}}}"#, join(args, ","), n),
+            Some(Err(e)) => format!(r#"{{"args":[{}],"error":"{}"}}"#, join(args, ","), e),
+            None => "null".to_string(),
+        };
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                format!(
+                    r#"{{"problem":{},"op":"{}","horizontal":{},"vertical":{},"differs":{}}}"#,
+                    i + 1,
+                    row.op,
+                    reading(&row.args, Some(&row.result)),
+                    reading(&row.args_vertical, row.result_vertical.as_ref()),
+                    row.differs()
+                )
+            })
+            .collect();
+        format!("[{}]", rows.join(","))
+    }
+
+    /// Renders CSV with each reading's operands joined by spaces.
+    fn to_csv(&self) -> String {
+        let mut out = "problem,op,horizontal,result,vertical,vertical_result,differs\n".to_string();
+        for (i, row) in self.rows.iter().enumerate() {
+            out += &format!(
+                "{},{},{},{},{},{},{}\n",
+                i + 1,
+                row.op,
+                join(&row.args, " "),
+                result_text(Some(&row.result)),
+                join(&row.args_vertical, " "),
+                result_text(row.result_vertical.as_ref()),
+                row.differs()
+            );
+        }
+        out
+    }
+}
+
+#[aoc(day6, part1, Table)]
+fn part1_table(input: &[Problem]) -> anyhow::Result<String> {
+    Ok(format!("\n{}", ResultTable::new(input)?.to_table()))
+}
+
+#[aoc(day6, part1, TableJson)]
+fn part1_table_json(input: &[Problem]) -> anyhow::Result<String> {
+    Ok(ResultTable::new(input)?.to_json())
+}
+
+#[aoc(day6, part1, TableCsv)]
+fn part1_table_csv(input: &[Problem]) -> anyhow::Result<String> {
+    Ok(format!("\n{}", ResultTable::new(input)?.to_csv()))
+}
+
+/// Writes problems back out as a worksheet that parses to the same `args`,
+/// references and operators.
+///
+/// Each problem's numbers go one per line, right-aligned in its block, with
+/// a single blank column between blocks and the operators on the last line.
+fn write_horizontal(problems: &[Problem]) -> String {
+    let cells: Vec<Vec<String>> = problems.iter().map(Problem::cells).collect();
+    let widths: Vec<usize> = problems
+        .iter()
+        .zip(&cells)
+        .map(|(p, cells)| {
+            let widest = cells.iter().map(String::len).max().unwrap_or(0);
+            widest.max(p.op.symbol().len())
+        })
+        .collect();
+    let height = cells.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut lines: Vec<String> = (0..height)
+        .map(|r| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cells, &w)| format!("{:>w$}", cells.get(r).map_or("", String::as_str)))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    lines.push(
+        problems
+            .iter()
+            .zip(&widths)
+            .map(|(p, &w)| format!("{:<w$}", p.op.symbol()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    lines
+        .iter()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect()
+}
+
+/// A row of a vertical block has one digit per number, and 19 digits is the
+/// most that always fits in `u64`.
+const MAX_VERTICAL_NUMBERS: usize = 19;
+
+/// Writes problems back out as a worksheet that parses to the same
+/// `args_vertical` and operators.
+///
+/// Each number is a column of digits read top to bottom. Shorter numbers
+/// are padded with leading zeros at the top, so every row of a block is
+/// also a single number and the worksheet passes the row alignment checks
+/// in `parse`.
+///
+/// Fails for a problem with more than `MAX_VERTICAL_NUMBERS` numbers, whose
+/// rows would not fit in `u64`, and for one with references, which have no
+/// vertical reading.
+fn write_vertical(problems: &[Problem]) -> anyhow::Result<String> {
+    for (i, p) in problems.iter().enumerate() {
+        if !p.refs.is_empty() {
+            bail!("Problem {}: references cannot be written vertically", i + 1);
+        }
+        if p.args_vertical.len() > MAX_VERTICAL_NUMBERS {
+            bail!(
+                "Problem {}: {} numbers do not fit in a vertical layout, at most {} do",
+                i + 1,
+                p.args_vertical.len(),
+                MAX_VERTICAL_NUMBERS
+            );
+        }
+    }
+
+    let height = problems
+        .iter()
+        .flat_map(|p| &p.args_vertical)
+        .map(|n| n.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let columns: Vec<Vec<Vec<char>>> = problems
+        .iter()
+        .map(|p| {
+            p.args_vertical
+                .iter()
+                .map(|n| format!("{:0>height$}", n).chars().collect())
+                .collect()
+        })
+        .collect();
+    let widths: Vec<usize> = problems
+        .iter()
+        .map(|p| p.args_vertical.len().max(p.op.symbol().len()))
+        .collect();
+
+    let mut lines: Vec<String> = (0..height)
+        .map(|r| {
+            columns
+                .iter()
+                .zip(&widths)
+                .map(|(columns, &w)| {
+                    let digits: String = columns.iter().map(|column| column[r]).collect();
+                    format!("{:<w$}", digits)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect();
+    lines.push(
+        problems
+            .iter()
+            .zip(&widths)
+            .map(|(p, &w)| format!("{:<w$}", p.op.symbol()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+
+    Ok(lines
+        .iter()
+        .map(|line| format!("{}\n", line.trim_end()))
+        .collect())
+}
+
+/// The input rewritten in the aligned horizontal layout.
+#[aoc(day6, part1, Normalise)]
+fn part1_normalise(input: &[Problem]) -> String {
+    format!("\n{}", write_horizontal(input))
+}
+
+/// The input rewritten in the aligned vertical layout.
+#[aoc(day6, part2, Normalise)]
+fn part2_normalise(input: &[Problem]) -> anyhow::Result<String> {
+    Ok(format!("\n{}", write_vertical(input)?))
+}
+
+#[aoc_generator(day6, part1, Worksheets)]
+fn parse_worksheets_part1(input: &str) -> Result<Vec<Worksheet>, ParseError> {
+    parse_worksheets(input)
+}
+
+/// Answers every worksheet separately, once for each of its readings.
+#[aoc(day6, part1, Worksheets)]
+fn part1_worksheets(worksheets: &[Worksheet]) -> anyhow::Result<String> {
+    let mut out = String::from("\n");
+    for (i, worksheet) in worksheets.iter().enumerate() {
+        out += &format!("worksheet {} (line {})\n", i + 1, worksheet.line);
+        for &reading in &worksheet.readings {
+            let total = grand_total(&worksheet.problems, |_| reading)
+                .with_context(|| format!("Worksheet {}, reading {}", i + 1, reading))?;
+            out += &format!("  {}: {}\n", reading, total);
+        }
+    }
+    Ok(out)
+}
+
+pub(crate) struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+    type Input = Vec<Problem>;
+
+    fn parse(input: &str) -> Result<Vec<Problem>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Vec<Problem>) -> anyhow::Result<Answer> {
+        Ok(part1(input)?.into())
+    }
+
+    fn part2(input: &Vec<Problem>) -> anyhow::Result<Answer> {
+        Ok(part2(input)?.into())
+    }
+}
+
+/// Named alternates for the standalone runner, see [`crate::registry`].
+pub(crate) const ALTERNATES: &[Solver] = &[
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Exact"),
+        run: |input| Ok(part1_exact(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Reference"),
+        run: |input| Ok(part1_reference(&parse_reference_part1(input))?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Expressions"),
+        run: |input| Ok(part1_expressions(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Table"),
+        run: |input| Ok(part1_table(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("TableJson"),
+        run: |input| Ok(part1_table_json(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("TableCsv"),
+        run: |input| Ok(part1_table_csv(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Normalise"),
+        run: |input| Ok(part1_normalise(&parse(input)?).into()),
+    },
+    Solver {
+        day: 6,
+        part: 1,
+        name: Some("Worksheets"),
+        run: |input| Ok(part1_worksheets(&parse_worksheets_part1(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 2,
+        name: Some("Exact"),
+        run: |input| Ok(part2_exact(&parse(input)?)?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 2,
+        name: Some("Reference"),
+        run: |input| Ok(part2_reference(&parse_reference_part2(input))?.into()),
+    },
+    Solver {
+        day: 6,
+        part: 2,
+        name: Some("Normalise"),
+        run: |input| Ok(part2_normalise(&parse(input)?)?.into()),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen::Rng;
 
     #[test]
     fn part1_example() {
         assert_eq!(
-            part1(&parse(
-                &vec![
-                    "123 328  51 64 ",
-                    " 45 64  387 23 ",
-                    "  6 98  215 314",
-                    "*   +   *   +  "
-                ]
-                .join("\n")
-            )),
-            "4277556"
+            part1(
+                &parse(
+                    &[
+                        "123 328  51 64 ",
+                        " 45 64  387 23 ",
+                        "  6 98  215 314",
+                        "*   +   *   +  "
+                    ]
+                    .join("\n")
+                )
+                .unwrap()
+            )
+            .unwrap(),
+            4277556
         );
     }
 
     #[test]
     fn part2_example() {
         assert_eq!(
-            part2(&parse(
-                &vec![
-                    "123 328  51 64 ",
-                    " 45 64  387 23 ",
-                    "  6 98  215 314",
-                    "*   +   *   +  "
-                ]
-                .join("\n")
-            )),
-            "3263827"
+            part2(
+                &parse(
+                    &[
+                        "123 328  51 64 ",
+                        " 45 64  387 23 ",
+                        "  6 98  215 314",
+                        "*   +   *   +  "
+                    ]
+                    .join("\n")
+                )
+                .unwrap()
+            )
+            .unwrap(),
+            3263827
+        );
+    }
+
+    #[test]
+    fn operators() {
+        let input = parse(
+            &[
+                "100 10 2   7   9 100",
+                "  5  3 3   2   4   7",
+                "  2  2 2   5   6   3",
+                "/   -  ^ min max   %",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let ops: Vec<String> = input.iter().map(|p| p.op.to_string()).collect();
+        assert_eq!(ops, vec!["/", "-", "^", "min", "max", "%"]);
+
+        let results: Vec<u64> = input
+            .iter()
+            .map(|p| p.op.checked_eval(&p.args).unwrap())
+            .collect();
+        // 100 / 5 / 2, 10 - 3 - 2, 2 ^ (3 ^ 2), min, max, 100 % 7 % 3
+        assert_eq!(results, vec![10, 5, 512, 2, 9, 2]);
+    }
+
+    #[test]
+    fn operator_properties() {
+        for symbol in ["*", "+", "-", "/", "^", "min", "max", "%"] {
+            let op = Op::from_symbol(symbol).unwrap();
+            assert_eq!(op.to_string(), symbol);
+            if let Some(identity) = op.identity() {
+                assert_eq!(op.checked_eval(&[]), Ok(identity));
+                assert_eq!(op.checked_apply(7, identity), Ok(7), "{}", symbol);
+            }
+        }
+        assert_eq!(Op::Pow.fold_order(), Fold::Right);
+        assert_eq!(Op::Minus.fold_order(), Fold::Left);
+    }
+
+    #[test]
+    fn unknown_operator() {
+        let err = parse("1 2 3\n4 5 6\n+ ? *").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 6, line 3, column 3: unknown operator '?'"
+        );
+
+        let err = parse("1 2\n+ * *").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 6, line 2, column 5: operator '*' has no numbers in its columns"
+        );
+    }
+
+    #[test]
+    fn operator_line_anywhere() {
+        let example = ["123 328  51 64 ", " 45 64  387 23 ", "  6 98  215 314"];
+        let ops = "*   +   *   +  ";
+
+        for at in 0..=example.len() {
+            let mut lines = example.to_vec();
+            lines.insert(at, ops);
+            let input = parse(&lines.join("\n")).unwrap();
+            assert_eq!(part1(&input).unwrap(), 4277556);
+            assert_eq!(part2(&input).unwrap(), 3263827);
+        }
+    }
+
+    #[test]
+    fn ragged_lines_and_tabs() {
+        // no trailing spaces, and a later line longer than the first
+        let input =
+            parse("123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +").unwrap();
+        assert_eq!(part1(&input).unwrap(), 4277556);
+        assert_eq!(part2(&input).unwrap(), 3263827);
+
+        let input = parse("1\n22\n333 4444\n+   *").unwrap();
+        assert_eq!(input[0].args, vec![1, 22, 333]);
+        assert_eq!(input[0].args_vertical, vec![123, 23, 3]);
+        assert_eq!(input[1].args, vec![4444]);
+
+        // a tab advances to column 9
+        let input = parse("12\t5\n 3\t6\n+\t*").unwrap();
+        assert_eq!(input[0].args, vec![12, 3]);
+        assert_eq!(input[1].args, vec![5, 6]);
+        assert_eq!(part1(&input).unwrap(), 45);
+    }
+
+    #[test]
+    fn alignment_errors() {
+        let err = parse("12 3\n1234\n+").err().unwrap();
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Day 6, line 1, column 1: '12 3' is not a single number: invalid digit found in string"
+        );
+
+        let err = parse("1 2\n3 4\n+").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 6, line 3, column 3: problem in columns 3-3 has no operator"
+        );
+
+        let err = parse("1 2\n+ *\n- /").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 6, line 3: second operator line, the first is line 2"
+        );
+
+        let err = parse("1 2\n3 4").err().unwrap();
+        assert_eq!(err.to_string(), "Day 6, line 1: no operator line found");
+
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn overflow_reports_problem() {
+        let input = parse("1 4294967296 3\n2 4294967296 4\n+ *          +").unwrap();
+        let err = part1(&input).err().unwrap();
+        assert_eq!(err.to_string(), "Problem 2 (*): overflowed u64");
+        assert_eq!(
+            part1_exact(&input).unwrap().to_string(),
+            "18446744073709551626"
+        );
+
+        let input = parse(
+            &[
+                "18446744073709551615 1",
+                "                   0 0",
+                "+                    +",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        let err = part1(&input).err().unwrap();
+        assert_eq!(err.to_string(), "Grand total overflowed u64 at problem 2");
+        assert_eq!(
+            part1_exact(&input).unwrap().to_string(),
+            "18446744073709551616"
+        );
+    }
+
+    #[test]
+    fn checked_errors() {
+        assert_eq!(Op::Minus.checked_eval(&[3, 5]), Err(ArithError::Negative));
+        assert_eq!(
+            Op::Div.checked_eval(&[3, 0]),
+            Err(ArithError::DivisionByZero)
+        );
+        assert_eq!(
+            Op::Rem.checked_eval(&[3, 0]),
+            Err(ArithError::DivisionByZero)
+        );
+        assert_eq!(Op::Pow.checked_eval(&[2, 64]), Err(ArithError::Overflow));
+        assert_eq!(Op::Pow.checked_eval(&[2, 63]), Ok(1 << 63));
+        assert_eq!(
+            Op::Pow.checked_eval(&[1, 4294967296]),
+            Err(ArithError::Overflow)
+        );
+    }
+
+    #[test]
+    fn exact_results() {
+        assert_eq!(
+            Op::Pow.big_eval(&[2, 3, 4]).unwrap().to_string(),
+            "2417851639229258349412352"
+        );
+        assert_eq!(Op::Minus.big_eval(&[3, 5]).unwrap().to_string(), "-2");
+        assert_eq!(Op::Div.big_eval(&[1, 0]), Err(ArithError::DivisionByZero));
+
+        let input = parse(
+            &[
+                "18446744073709551615 18446744073709551615",
+                "18446744073709551615 18446744073709551615",
+                "*                    +",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        assert_eq!(part1_exact(&input).unwrap(), BigInt::from(u128::MAX));
+        assert!(part1(&input).is_err());
+    }
+
+    #[test]
+    fn exact_power_limit() {
+        assert_eq!(
+            Op::Pow.big_eval(&[2, 4000000000]),
+            Err(ArithError::Overflow)
+        );
+        assert_eq!(
+            Op::Pow.big_eval(&[2, MAX_EXACT_BITS + 1]),
+            Err(ArithError::Overflow)
+        );
+        assert_eq!(
+            Op::Pow.big_eval(&[2, MAX_EXACT_BITS]).unwrap().bits(),
+            MAX_EXACT_BITS + 1
+        );
+        // 0 and 1 stay small whatever the exponent
+        assert_eq!(Op::Pow.big_eval(&[1, 4000000000]), Ok(BigInt::from(1)));
+        assert_eq!(Op::Pow.big_eval(&[0, 4000000000]), Ok(BigInt::from(0)));
+
+        let input = parse("2\n4000000000\n^").unwrap();
+        assert_eq!(
+            part1_exact(&input).err().unwrap().to_string(),
+            "Problem 1 (^): overflowed u64"
+        );
+    }
+
+    #[test]
+    fn exact_matches_checked_on_example() {
+        let input = parse(
+            &[
+                "123 328  51 64 ",
+                " 45 64  387 23 ",
+                "  6 98  215 314",
+                "*   +   *   +  ",
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        assert_eq!(part1_exact(&input).unwrap(), part1(&input).unwrap().into());
+        assert_eq!(part2_exact(&input).unwrap(), part2(&input).unwrap().into());
+    }
+
+    const EXAMPLE: &str = include_str!("../examples/day6/example.txt");
+
+    #[test]
+    fn reference() {
+        let lines = parse_reference_part1(EXAMPLE);
+        assert_eq!(part1_reference(&lines).unwrap(), BigInt::from(4277556));
+        assert_eq!(part2_reference(&lines).unwrap(), BigInt::from(3263827));
+
+        // ragged lines, and a product past u64
+        let lines = parse_reference_part1("99999999999\n99999999999\n*\n");
+        assert_eq!(
+            part1_reference(&lines).unwrap(),
+            BigInt::from(99999999999u64).pow(2)
+        );
+
+        // columns skip the gap a shorter number leaves
+        let lines = parse_reference_part2("12\n3\n45\n+");
+        assert_eq!(part2_reference(&lines).unwrap(), BigInt::from(134 + 25));
+
+        for input in ["", "1 2\n+", "1\n-", "x\n+"] {
+            assert!(
+                part1_reference(&parse_reference_part1(input)).is_err(),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn readings() {
+        let input = parse(EXAMPLE).unwrap();
+        let read = |reading: &str| {
+            let reading = Reading::parse(reading).unwrap();
+            input
+                .iter()
+                .map(|p| p.read(reading).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(read("rows")[0], input[0].args);
+        assert_eq!(read("columns")[0], input[0].args_vertical);
+        assert_eq!(read("rows")[0], vec![123, 45, 6]);
+        assert_eq!(read("rows, right-to-left")[0], vec![321, 54, 6]);
+        assert_eq!(read("rows bottom-to-top")[0], vec![6, 45, 123]);
+        assert_eq!(read("columns")[0], vec![1, 24, 356]);
+        assert_eq!(read("columns right-to-left")[0], vec![356, 24, 1]);
+        assert_eq!(read("columns bottom-to-top")[0], vec![1, 42, 653]);
+        assert_eq!(
+            read("columns right-to-left bottom-to-top")[3],
+            vec![4, 134, 326]
+        );
+
+        assert!(Reading::parse("diagonal").is_err());
+        assert_eq!(
+            Reading::parse("columns,right-to-left").unwrap().to_string(),
+            "columns, right-to-left"
+        );
+    }
+
+    #[test]
+    fn multiple_worksheets() {
+        let input = format!(
+            "{}\n\n# read: columns right-to-left\n# read: rows\n{}\n\n\n1 2\n3 4\n- *\n",
+            EXAMPLE.trim_end_matches('\n'),
+            EXAMPLE.trim_end_matches('\n')
+        );
+
+        let worksheets = parse_worksheets(&input).unwrap();
+        assert_eq!(worksheets.len(), 3);
+        assert_eq!(worksheets[1].line, 6);
+        assert_eq!(worksheets[2].line, 14);
+        assert_eq!(worksheets[2].problems[0].args, vec![1, 3]);
+
+        assert_eq!(
+            part1_worksheets(&worksheets).err().unwrap().to_string(),
+            "Worksheet 3, reading rows"
+        );
+
+        let input = input.replace("1 2\n3 4\n- *", "3 2\n1 4\n- *");
+        let worksheets = parse_worksheets(&input).unwrap();
+        assert_eq!(
+            part1_worksheets(&worksheets).unwrap(),
+            "
+worksheet 1 (line 1)
+  rows: 4277556
+  columns: 3263827
+worksheet 2 (line 6)
+  columns, right-to-left: 3263827
+  rows: 4277556
+worksheet 3 (line 14)
+  rows: 10
+  columns: 55
+"
+        );
+
+        // the flat problem list answers all worksheets together, each with
+        // its own readings
+        let problems = parse(&input).unwrap();
+        assert_eq!(problems.len(), 10);
+        assert_eq!(part1(&problems).unwrap(), 4277556 + 3263827 + 10);
+        assert_eq!(part2(&problems).unwrap(), 3263827 + 4277556 + 55);
+        assert_eq!(
+            part1_exact(&problems).unwrap(),
+            BigInt::from(part1(&problems).unwrap())
+        );
+
+        // one reading answers both parts
+        let problems = parse(
+            "# read: rows right-to-left
+12 3
+4  5
++  *",
+        )
+        .unwrap();
+        assert_eq!(part1(&problems).unwrap(), 21 + 4 + 3 * 5);
+        assert_eq!(part2(&problems).unwrap(), part1(&problems).unwrap());
+    }
+
+    #[test]
+    fn worksheet_errors() {
+        let err = parse("# read: sideways\n1\n+").err().unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Day 6, line 1, column 8: Unknown reading option 'sideways'"
+        );
+
+        let err = parse("1 2\n+ +\n\n3 4\n5 6").err().unwrap();
+        assert_eq!(err.to_string(), "Day 6, line 4: no operator line found");
+
+        let input = "1\n+\n\n# read: rows\n# read: columns\n# read: rows right-to-left\n1\n+";
+        assert_eq!(
+            parse(input).err().unwrap().to_string(),
+            "Day 6, line 4: worksheet has 3 readings, parts 1 and 2 take at most 2"
+        );
+        assert_eq!(parse_worksheets(input).unwrap()[1].readings.len(), 3);
+    }
+
+    #[test]
+    fn malformed_input() {
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("1\n+\n1\n-"),
+            "Day 6, line 4: second operator line, the first is line 2"
+        );
+        assert_eq!(err("é\n1"), "Day 6, line 1, column 1: unknown operator 'é'");
+        assert_eq!(
+            err("99999999999999999999\n+"),
+            "Day 6, line 1, column 1: '99999999999999999999' is not a single number"
+        );
+        let err = parse(&format!("{}+", "9\n".repeat(20))).err().unwrap();
+        assert_eq!((err.day, err.line, err.column), (6, 21, Some(1)));
+        assert_eq!(err.message(), "columns 1-1");
+
+        for input in [
+            "",
+            "\n\n",
+            "+",
+            "=",
+            "=P",
+            "#",
+            "# read:",
+            "\0",
+            "1\n\0",
+            "\t=P1\n\t+",
+        ] {
+            let _ = parse(input);
+        }
+    }
+
+    #[test]
+    fn expressions() {
+        let input = parse("=P2 10  4\n  3 =P3 5\n*   +   *").unwrap();
+        assert_eq!(
+            input[1].refs,
+            vec![Reference {
+                position: 1,
+                problem: 2
+            }]
+        );
+        assert_eq!(evaluate_expressions(&input).unwrap(), vec![90, 30, 20]);
+        assert_eq!(part1_expressions(&input).unwrap(), 90);
+        assert_eq!(
+            format!("{:#}", part1(&input).err().unwrap()),
+            "Problem 1: References other problems, answer it as an expression"
+        );
+
+        let written = write_horizontal(&input);
+        assert_eq!(written, "=P2  10 4\n  3 =P3 5\n*   +   *\n");
+        assert_eq!(part1_expressions(&parse(&written).unwrap()).unwrap(), 90);
+
+        // without references every problem is a root
+        let example = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            part1_expressions(&example).unwrap(),
+            part1(&example).unwrap()
+        );
+
+        // references stay within their worksheet
+        let input = parse("1\n+\n\n=P2 2\n+   *").unwrap();
+        assert_eq!(input[1].refs[0].problem, 2);
+        assert_eq!(part1_expressions(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn long_reference_chain() {
+        // each problem references the next, 200 000 deep
+        const LEN: usize = 200_000;
+        let cells: Vec<String> = (2..=LEN)
+            .map(|p| format!("=P{}", p))
+            .chain(["1".to_string()])
+            .collect();
+        let ops: Vec<String> = cells
+            .iter()
+            .map(|c| format!("{:<1$}", "+", c.len()))
+            .collect();
+        let input = parse(&format!("{}\n{}", cells.join(" "), ops.join(" "))).unwrap();
+        assert_eq!(input[0].refs[0].problem, 1);
+
+        let order = evaluation_order(&input).unwrap();
+        assert_eq!(order, (0..LEN).rev().collect::<Vec<_>>());
+        assert_eq!(part1_expressions(&input).unwrap(), 1);
+    }
+
+    #[test]
+    fn expression_errors() {
+        let err = |input: &str| {
+            format!(
+                "{:#}",
+                part1_expressions(&parse(input).unwrap()).err().unwrap()
+            )
+        };
+        assert_eq!(err("=P2 =P1\n+   *"), "Reference cycle: P1 -> P2 -> P1");
+        assert_eq!(err("=P1\n+"), "Reference cycle: P1 -> P1");
+        assert_eq!(
+            err("=P2 18446744073709551615\n  2 1\n*   +"),
+            "Problem 2 (+): overflowed u64"
+        );
+
+        let err = |input: &str| parse(input).err().unwrap().to_string();
+        assert_eq!(
+            err("1 =P5\n+ *"),
+            "Day 6, line 1, column 3: '=P5' but the worksheet has 2 problems"
+        );
+        assert_eq!(
+            err("=Q1\n+"),
+            "Day 6, line 1, column 1: invalid reference '=Q1'"
+        );
+        assert_eq!(
+            err("=P0\n+"),
+            "Day 6, line 1, column 1: invalid reference '=P0'"
+        );
+    }
+
+    fn problem(op: Op, args: Vec<u64>) -> Problem {
+        Problem {
+            args: args.clone(),
+            args_vertical: args,
+            op,
+            digits: vec![],
+            refs: vec![],
+            readings: [Reading::ROWS, Reading::COLUMNS],
+        }
+    }
+
+    #[test]
+    fn write_example() {
+        let input = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            write_horizontal(&input),
+            "123 328  51  64
+ 45  64 387  23
+  6  98 215 314
+*   +   *   +
+"
+        );
+        assert_eq!(
+            write_vertical(&input).unwrap(),
+            "003 320 051 640
+025 640 387 230
+146 988 215 314
+*   +   *   +
+"
+        );
+    }
+
+    #[test]
+    fn write_wide_operators() {
+        let problems = vec![
+            problem(Op::Min, vec![5, 12]),
+            problem(Op::Pow, vec![2, 3, 2]),
+        ];
+        assert_eq!(write_horizontal(&problems), "  5 2\n 12 3\n    2\nmin ^\n");
+        assert_eq!(
+            write_vertical(&problems).unwrap(),
+            "01  000\n52  232\nmin ^\n"
+        );
+    }
+
+    #[test]
+    fn write_round_trip() {
+        let mut rng = Rng::new(2025);
+        let mut next = |bound: u64| rng.range(0, bound - 1);
+        let ops = [
+            Op::Mul,
+            Op::Plus,
+            Op::Minus,
+            Op::Div,
+            Op::Pow,
+            Op::Min,
+            Op::Max,
+            Op::Rem,
+        ];
+
+        for _ in 0..500 {
+            let problems: Vec<Problem> = (0..1 + next(6))
+                .map(|_| {
+                    // past MAX_VERTICAL_NUMBERS now and then
+                    let args = (0..1 + next(24))
+                        .map(|_| {
+                            let digits = 1 + next(7) as u32;
+                            next(10u64.pow(digits))
+                        })
+                        .collect();
+                    problem(ops[next(ops.len() as u64) as usize], args)
+                })
+                .collect();
+
+            // parse reads columns too, so neither layout of these parses
+            let too_many = problems
+                .iter()
+                .position(|p| p.args_vertical.len() > MAX_VERTICAL_NUMBERS);
+            if let Some(i) = too_many {
+                let err = write_vertical(&problems).err().unwrap();
+                assert!(
+                    err.to_string().starts_with(&format!("Problem {}: ", i + 1)),
+                    "{}",
+                    err
+                );
+                continue;
+            }
+
+            let text = write_horizontal(&problems);
+            let parsed = parse(&text).unwrap();
+            assert_eq!(parsed.len(), problems.len(), "{}", text);
+            for (a, b) in problems.iter().zip(&parsed) {
+                assert_eq!((a.op, &a.args), (b.op, &b.args), "{}", text);
+            }
+            assert_eq!(write_horizontal(&parsed), text);
+
+            let text = write_vertical(&problems).unwrap();
+            let parsed = parse(&text).unwrap();
+            assert_eq!(parsed.len(), problems.len(), "{}", text);
+            for (a, b) in problems.iter().zip(&parsed) {
+                assert_eq!(
+                    (a.op, &a.args_vertical),
+                    (b.op, &b.args_vertical),
+                    "{}",
+                    text
+                );
+            }
+            assert_eq!(write_vertical(&parsed).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn write_vertical_limits() {
+        let wide = vec![
+            problem(Op::Plus, vec![1; 19]),
+            problem(Op::Max, vec![9; 20]),
+        ];
+        assert_eq!(
+            write_vertical(&wide).err().unwrap().to_string(),
+            "Problem 2: 20 numbers do not fit in a vertical layout, at most 19 do"
+        );
+        let text = write_vertical(&wide[..1]).unwrap();
+        assert_eq!(text, format!("{}\n+\n", "1".repeat(19)));
+
+        let input = parse("=P2 10  4\n  3 =P3 5\n*   +   *").unwrap();
+        assert_eq!(
+            part2_normalise(&input).err().unwrap().to_string(),
+            "Problem 1: references cannot be written vertically"
+        );
+    }
+
+    #[test]
+    fn normalise_messy_worksheet() {
+        let messy = "12\t 7\n3     80\n+\t*";
+        let input = parse(messy).unwrap();
+        assert_eq!(part1_normalise(&input), "\n12  7\n 3 80\n+  *\n");
+        let normalised = parse(&write_horizontal(&input)).unwrap();
+        assert_eq!(part1(&normalised).unwrap(), part1(&input).unwrap());
+    }
+
+    #[test]
+    fn result_table() {
+        let input = parse(EXAMPLE).unwrap();
+        let table = ResultTable::new(&input).unwrap();
+        assert_eq!(
+            table.rows[0],
+            ResultRow {
+                op: Op::Mul,
+                args: vec![123, 45, 6],
+                result: Ok(33210),
+                args_vertical: vec![1, 24, 356],
+                result_vertical: Some(Ok(8544)),
+            }
+        );
+        assert!(table.rows.iter().all(ResultRow::differs));
+
+        assert_eq!(
+            table.to_table(),
+            "#  OP  HORIZONTAL  RESULT   VERTICAL    RESULT   DIFFERS
+1  *   123 45 6    33210    1 24 356    8544     *
+2  +   328 64 98   490      369 248 8   625      *
+3  *   51 387 215  4243455  32 581 175  3253600  *
+4  +   64 23 314   401      623 431 4   1058     *
+4 of 4 problems differ
+"
+        );
+    }
+
+    #[test]
+    fn result_table_references() {
+        let input = parse("=P2 10  4\n  3 =P3 5\n*   +   *").unwrap();
+        let table = ResultTable::new(&input).unwrap();
+        let horizontal: Vec<_> = table
+            .rows
+            .iter()
+            .map(|row| (row.args.clone(), row.result))
+            .collect();
+        assert_eq!(
+            horizontal,
+            [
+                (vec![30, 3], Ok(90)),
+                (vec![10, 20], Ok(30)),
+                (vec![4, 5], Ok(20)),
+            ]
+        );
+        assert_eq!(
+            table.to_table(),
+            "#  OP  HORIZONTAL  RESULT  VERTICAL  RESULT  DIFFERS
+1  *   30 3        90
+2  +   10 20       30
+3  *   4 5         20      45        45      *
+1 of 3 problems differ
+"
+        );
+        assert!(table.to_json().starts_with(
+            r#"[{"problem":1,"op":"*","horizontal":{"args":[30,3],"result":90},"vertical":null,"differs":false}"#
+        ));
+
+        let input = parse("=P2 0\n  3 5\n*   -").unwrap();
+        assert_eq!(
+            ResultTable::new(&input).unwrap_err().to_string(),
+            "Problem 1 references problem 2, which has no result: went below zero"
+        );
+        let input = parse("=P2 =P1\n  3   4\n*   +").unwrap();
+        assert!(ResultTable::new(&input).is_err());
+    }
+
+    #[test]
+    fn result_table_formats() {
+        let input = parse("11 10\n 1  5\n+  -").unwrap();
+        let table = ResultTable::new(&input).unwrap();
+
+        assert_eq!(
+            table.to_json(),
+            r#"[{"problem":1,"op":"+","horizontal":{"args":[11,1],"result":12},"vertical":{"args":[1,11],"result":12},"differs":false},{"problem":2,"op":"-","horizontal":{"args":[10,5],"result":5},"vertical":{"args":[1,5],"error":"went below zero"},"differs":true}]"#
+        );
+        assert_eq!(
+            table.to_csv(),
+            "problem,op,horizontal,result,vertical,vertical_result,differs
+1,+,11 1,12,1 11,12,false
+2,-,10 5,5,1 5,went below zero,true
+"
         );
     }
 }
@@ -0,0 +1,297 @@
+//! Differential tests: every main solver against an obvious, slow one on
+//! thousands of inputs from [`crate::gen`].
+//!
+//! Every day lists its slow solvers as `Reference` alternates. When the two
+//! disagree, the input is shrunk to the smallest one that still makes them
+//! disagree before failing.
+//!
+//! The slow solvers exist for these tests: each follows the puzzle text as
+//! directly as it can, brute force included, so it shares no shortcut with
+//! the main solver that could hide the same bug. Days 1 to 5 do share the
+//! main parser, which the unit and fuzz tests cover; day 6's parser does most
+//! of the work, so its references read the worksheet themselves.
+
+use crate::gen::{self, Rng};
+use crate::registry;
+use crate::solution::Answer;
+
+/// Inputs generated for each case, unless `DIFFERENTIAL_RUNS` sets another
+/// number.
+const RUNS: u64 = 2000;
+
+/// The reference day 2 solvers walk every ID, so shrinking skips inputs
+/// covering more than this.
+const MAX_DAY2_IDS: u64 = 100_000;
+
+/// A part whose main solver must agree with a named alternate.
+struct Case {
+    day: u32,
+    part: u32,
+    reference: &'static str,
+    /// A small input: small ones find the same bugs and shrink faster.
+    generate: fn(&mut Rng) -> String,
+}
+
+fn day1(rng: &mut Rng) -> String {
+    let count = rng.range(1, 50) as usize;
+    let zeroes = rng.index(count + 1);
+    gen::rotations(rng, count, zeroes, 5).input
+}
+
+fn day2(rng: &mut Rng) -> String {
+    let invalid = rng.index(6);
+    let decoys = rng.index(6).max(usize::from(invalid == 0));
+    gen::id_ranges(rng, invalid, decoys, 1000).input
+}
+
+fn day3(rng: &mut Rng) -> String {
+    // past 255 batteries, so indexes don't fit a byte
+    let (count, len) = (rng.range(1, 10) as usize, rng.range(12, 300) as usize);
+    gen::banks(rng, count, len).input
+}
+
+/// Rolls anywhere, unlike [`gen::clusters`]: no answer needs to be known,
+/// and a random grid finds more than separate clusters do.
+fn day4(rng: &mut Rng) -> String {
+    let (width, height) = (rng.range(1, 20), rng.range(1, 20));
+    let density = rng.range(0, 100) as f64 / 100.0;
+    let mut input = String::new();
+    for _ in 0..height {
+        let row: String = (0..width)
+            .map(|_| if rng.chance(density) { '@' } else { '.' })
+            .collect();
+        input += &row;
+        input.push('\n');
+    }
+    input
+}
+
+fn day5(rng: &mut Rng) -> String {
+    let blocks = rng.range(1, 5) as usize;
+    let (fresh, spoiled) = (rng.index(20), rng.index(20));
+    gen::database(rng, blocks, fresh, spoiled).input
+}
+
+fn day6(rng: &mut Rng) -> String {
+    let (count, rows) = (rng.range(1, 20) as usize, rng.range(1, 4) as usize);
+    gen::worksheet(rng, count, rows).input
+}
+
+const CASES: &[Case] = &[
+    Case {
+        day: 1,
+        part: 1,
+        reference: "Reference",
+        generate: day1,
+    },
+    Case {
+        day: 1,
+        part: 2,
+        reference: "Reference",
+        generate: day1,
+    },
+    Case {
+        day: 2,
+        part: 1,
+        reference: "Reference",
+        generate: day2,
+    },
+    Case {
+        day: 2,
+        part: 2,
+        reference: "Reference",
+        generate: day2,
+    },
+    Case {
+        day: 3,
+        part: 1,
+        reference: "Reference",
+        generate: day3,
+    },
+    Case {
+        day: 3,
+        part: 2,
+        reference: "Reference",
+        generate: day3,
+    },
+    Case {
+        day: 4,
+        part: 1,
+        reference: "Reference",
+        generate: day4,
+    },
+    Case {
+        day: 4,
+        part: 2,
+        reference: "Reference",
+        generate: day4,
+    },
+    Case {
+        day: 5,
+        part: 1,
+        reference: "Reference",
+        generate: day5,
+    },
+    Case {
+        day: 5,
+        part: 2,
+        reference: "Reference",
+        generate: day5,
+    },
+    Case {
+        day: 6,
+        part: 1,
+        reference: "Reference",
+        generate: day6,
+    },
+    Case {
+        day: 6,
+        part: 2,
+        reference: "Reference",
+        generate: day6,
+    },
+];
+
+impl Case {
+    /// The answers of the main and the reference solver, `None` for errors.
+    fn answers(&self, input: &str) -> (Option<Answer>, Option<Answer>) {
+        let solve = |name| {
+            registry::find(self.day, self.part, name)
+                .unwrap_or_else(|| panic!("no solver for {:?}", name))
+                .solve(input)
+                .ok()
+        };
+        (solve(None), solve(Some(self.reference)))
+    }
+
+    fn disagrees(&self, input: &str) -> bool {
+        let (main, reference) = self.answers(input);
+        main != reference
+    }
+}
+
+/// How many IDs the ranges of a day 2 input cover, at most.
+fn day2_ids(input: &str) -> u64 {
+    input
+        .lines()
+        .flat_map(|line| line.split(','))
+        .filter_map(|range| range.split_once('-'))
+        .filter_map(|(start, end)| {
+            Some((
+                start.trim().parse::<u64>().ok()?,
+                end.trim().parse::<u64>().ok()?,
+            ))
+        })
+        .fold(0, |ids: u64, (start, end)| {
+            ids.saturating_add(end.saturating_sub(start))
+        })
+}
+
+/// The smallest variant of `input` that still `fails`, cutting runs of lines,
+/// then of comma separated items, then single characters, and finally
+/// lowering each number, until none of those cuts fails any more.
+fn shrink(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut input = input.to_string();
+    'shrinking: loop {
+        for separator in ['\n', ','] {
+            let pieces: Vec<&str> = input.split(separator).collect();
+            let mut size = pieces.len() / 2;
+            while size > 0 {
+                for start in (0..pieces.len()).step_by(size) {
+                    let end = (start + size).min(pieces.len());
+                    let candidate = [&pieces[..start], &pieces[end..]]
+                        .concat()
+                        .join(&separator.to_string());
+                    if fails(&candidate) {
+                        input = candidate;
+                        continue 'shrinking;
+                    }
+                }
+                size /= 2;
+            }
+        }
+
+        for (i, c) in input.char_indices() {
+            let candidate = format!("{}{}", &input[..i], &input[i + c.len_utf8()..]);
+            if fails(&candidate) {
+                input = candidate;
+                continue 'shrinking;
+            }
+        }
+
+        let mut start = 0;
+        while let Some(offset) = input[start..].find(|c: char| c.is_ascii_digit()) {
+            let from = start + offset;
+            let to = input[from..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(input.len(), |len| from + len);
+            if let Ok(n) = input[from..to].parse::<u64>() {
+                for smaller in [0, n / 2, n.saturating_sub(1)] {
+                    if smaller >= n {
+                        continue;
+                    }
+                    let candidate = format!("{}{}{}", &input[..from], smaller, &input[to..]);
+                    if fails(&candidate) {
+                        input = candidate;
+                        continue 'shrinking;
+                    }
+                }
+            }
+            start = to;
+        }
+        return input;
+    }
+}
+
+#[test]
+fn main_solvers_match_references() {
+    let runs = match std::env::var("DIFFERENTIAL_RUNS") {
+        Ok(runs) => runs.parse().expect("DIFFERENTIAL_RUNS is not a number"),
+        Err(_) => RUNS,
+    };
+    for case in CASES {
+        for seed in 0..runs {
+            let input = (case.generate)(&mut Rng::new(seed));
+            if !case.disagrees(&input) {
+                continue;
+            }
+            let shrunk = shrink(&input, |input| {
+                !(case.day == 2 && day2_ids(input) > MAX_DAY2_IDS) && case.disagrees(input)
+            });
+            let (main, reference) = case.answers(&shrunk);
+            panic!(
+                "Day {} - Part {} disagrees with {} on seed {}, shrunk to\n{}\nmain: {:?}\n{}: {:?}",
+                case.day, case.part, case.reference, seed, shrunk, main, case.reference, reference
+            );
+        }
+    }
+}
+
+#[test]
+fn shrinks_to_minimal_input() {
+    let large = |input: &str| {
+        input
+            .split(|c: char| !c.is_ascii_digit())
+            .any(|n| n.parse::<u64>().is_ok_and(|n| n >= 100))
+    };
+    assert_eq!(shrink("12,345\n7\n1000", large), "100");
+    assert_eq!(shrink("100", large), "100");
+
+    // part 1 only counts stops at 0, part 2 also passes
+    let disagrees = |input: &str| {
+        let stops = registry::find(1, 1, None).unwrap().solve(input).ok();
+        let passes = registry::find(1, 2, None).unwrap().solve(input).ok();
+        stops.is_some() && stops != passes
+    };
+    let input = day1(&mut Rng::new(3));
+    assert!(disagrees(&input));
+    let shrunk = shrink(&input, disagrees);
+    assert!(disagrees(&shrunk));
+    assert_eq!(shrunk.lines().count(), 1, "{}", shrunk);
+}
+
+#[test]
+fn day2_limit() {
+    assert_eq!(day2_ids("1-10,x-3,20-25\r"), 14);
+    assert_eq!(day2_ids("0-18446744073709551615,1-2"), u64::MAX);
+}
@@ -0,0 +1,104 @@
+//! The error every generator returns for input it cannot parse.
+
+use std::error::Error;
+use std::fmt;
+
+/// Where and why a day's input failed to parse.
+///
+/// Displays as `Day 6, line 3, column 7: message`; the underlying cause, if
+/// any, is the error's `source`.
+#[derive(Debug)]
+pub struct ParseError {
+    pub day: u32,
+    /// 1-based input line.
+    pub line: usize,
+    /// 1-based column, in characters, when the error is about part of a line.
+    pub column: Option<usize>,
+    message: String,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl ParseError {
+    pub fn new(day: u32, line: usize, message: impl Into<String>) -> Self {
+        ParseError {
+            day,
+            line,
+            column: None,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn with_column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    pub fn with_source(mut self, source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// The message without the location.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(
+                f,
+                "Day {}, line {}, column {}: {}",
+                self.day, self.line, column, self.message
+            ),
+            None => write!(f, "Day {}, line {}: {}", self.day, self.line, self.message),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source.as_deref().map(|e| e as &(dyn Error + 'static))
+    }
+}
+
+/// The column `part`, a slice of `line`, starts in.
+pub(crate) fn column_of(line: &str, part: &str) -> usize {
+    let offset = (part.as_ptr() as usize).saturating_sub(line.as_ptr() as usize);
+    line.get(..offset)
+        .map_or(0, |before| before.chars().count())
+        + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let err = ParseError::new(5, 3, "invalid range '1-x'");
+        assert_eq!(err.to_string(), "Day 5, line 3: invalid range '1-x'");
+        let err = err
+            .with_column(4)
+            .with_source(anyhow::anyhow!("Failed to parse ID 'x'"));
+        assert_eq!(
+            err.to_string(),
+            "Day 5, line 3, column 4: invalid range '1-x'"
+        );
+        assert_eq!((err.day, err.line, err.column), (5, 3, Some(4)));
+        assert_eq!(
+            format!("{:#}", anyhow::Error::new(err)),
+            "Day 5, line 3, column 4: invalid range '1-x': Failed to parse ID 'x'"
+        );
+    }
+
+    #[test]
+    fn columns() {
+        let line = "  ab→cd ef";
+        assert_eq!(column_of(line, line), 1);
+        assert_eq!(column_of(line, line.trim()), 3);
+        assert_eq!(column_of(line, &line[line.find('c').unwrap()..]), 6);
+    }
+}
@@ -0,0 +1,87 @@
+//! Tests generated by `build.rs`, one for every `examples/dayN/NAME.txt`.
+//!
+//! Each example's answers are in `NAME.toml` next to it. Parts left out are
+//! not checked, and a table checks a named alternate:
+//!
+//! ```toml
+//! part1 = 3
+//! part2 = 14
+//!
+//! [Coverage]
+//! part2 = 14
+//! ```
+//!
+//! Adding a regression case only takes the two files.
+
+use crate::registry;
+use crate::solution::Answer;
+use std::fs;
+use std::path::Path;
+
+/// Runs every answer listed for the example at `path`, and fails with all the
+/// ones that differ.
+fn check(day: u32, path: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+    let input = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", path.display(), e));
+    let sidecar = path.with_extension("toml");
+    let expected: toml::Table = fs::read_to_string(&sidecar)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", sidecar.display(), e))
+        .parse()
+        .unwrap_or_else(|e| panic!("Invalid {}: {}", sidecar.display(), e));
+
+    let mut answers = Vec::new();
+    for (key, value) in &expected {
+        match value {
+            toml::Value::Table(table) => {
+                answers.extend(table.iter().map(|(part, value)| (Some(key), part, value)))
+            }
+            value => answers.push((None, key, value)),
+        }
+    }
+    assert!(
+        !answers.is_empty(),
+        "{} lists no answers",
+        sidecar.display()
+    );
+
+    let mut failures = Vec::new();
+    for (name, part, value) in answers {
+        let part = match part.as_str() {
+            "part1" => 1,
+            "part2" => 2,
+            _ => panic!("{}: unknown key '{}'", sidecar.display(), part),
+        };
+        let expected = match value {
+            toml::Value::Integer(n) => Answer::from(*n),
+            toml::Value::String(s) => s.parse().unwrap_or_else(|e| match e {}),
+            value => panic!("{}: invalid answer {}", sidecar.display(), value),
+        };
+        let solver = registry::find(day, part, name.map(String::as_str)).unwrap_or_else(|| {
+            panic!(
+                "{}: no solver for part {} {:?}",
+                sidecar.display(),
+                part,
+                name
+            )
+        });
+        match solver.solve(&input) {
+            Ok(actual) if actual == expected => {}
+            Ok(actual) => failures.push(format!(
+                "{}: expected {}, got {}",
+                solver.label(),
+                expected,
+                actual
+            )),
+            Err(e) => failures.push(format!("{}: {:#}", solver.label(), e)),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\n{}",
+        path.display(),
+        failures.join("\n")
+    );
+}
+
+include!(concat!(env!("OUT_DIR"), "/examples.rs"));
@@ -0,0 +1,67 @@
+//! Entry points for the fuzz targets in `fuzz/`.
+//!
+//! Every target feeds arbitrary bytes to one day's generator, and the
+//! `solve` ones also run both parts on whatever parses. Errors are expected;
+//! a panic is a bug. Inputs that made a target panic are kept in
+//! `fuzz/regressions/<target>/` and replayed by generated tests.
+
+use crate::registry::{self, Parsed};
+
+fn generate(day: u32, data: &[u8]) -> Option<Box<dyn Parsed>> {
+    let input = std::str::from_utf8(data).ok()?;
+    let generator = registry::generators().into_iter().find(|g| g.day == day)?;
+    generator.parse(input).ok()
+}
+
+/// Runs the generator of `day` on `data`.
+pub fn parse(day: u32, data: &[u8]) {
+    generate(day, data);
+}
+
+/// Runs the generator of `day` on `data`, then both parts if it parsed.
+pub fn solve(day: u32, data: &[u8]) {
+    let Some(parsed) = generate(day, data) else {
+        return;
+    };
+    let _ = parsed.part1();
+    let _ = parsed.part2();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    /// Replays one saved input against the target it crashed.
+    fn replay(target: &str, path: &str) {
+        let (day, stage) = target
+            .strip_prefix("day")
+            .and_then(|target| target.split_once('_'))
+            .unwrap_or_else(|| panic!("'{}' is not a fuzz target", target));
+        let day: u32 = day.parse().unwrap();
+        let data = fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join(path)).unwrap();
+        match stage {
+            "parse" => parse(day, &data),
+            "solve" => solve(day, &data),
+            _ => panic!("'{}' is not a fuzz target", target),
+        }
+    }
+
+    #[test]
+    fn examples_run() {
+        for day in registry::days() {
+            let path = format!(
+                "{}/examples/day{}/example.txt",
+                env!("CARGO_MANIFEST_DIR"),
+                day
+            );
+            let data = fs::read(path).unwrap();
+            assert!(generate(day, &data).is_some(), "day {}", day);
+            solve(day, &data);
+        }
+        assert!(generate(1, b"\xff").is_none());
+    }
+
+    include!(concat!(env!("OUT_DIR"), "/fuzz_regressions.rs"));
+}
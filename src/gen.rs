@@ -0,0 +1,462 @@
+//! Seeded generators of puzzle inputs of any size, for stress tests and
+//! benchmarks.
+//!
+//! Every generator builds its input around a property chosen up front, such
+//! as where the dial stops at 0 or which IDs are invalid, so the answer it
+//! implies is known without solving. The same seed always gives the same
+//! input.
+
+use std::fmt::Write;
+
+/// SplitMix64: small, fast and good enough for test data.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..=high`.
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        assert!(low <= high, "empty range {}..={}", low, high);
+        match (high - low).checked_add(1) {
+            Some(span) => low + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    /// An index below `len`.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "no index below 0");
+        self.range(0, len as u64 - 1) as usize
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64) < p * (1u64 << 53) as f64
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// `count` true values among `len`, at random positions.
+fn marks(rng: &mut Rng, len: usize, count: usize) -> Vec<bool> {
+    assert!(count <= len, "{} marks do not fit in {}", count, len);
+    let mut marks = vec![false; len];
+    marks[..count].fill(true);
+    rng.shuffle(&mut marks);
+    marks
+}
+
+/// A day 1 rotation script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rotations {
+    pub input: String,
+    /// How often the dial stops at 0, the part 1 answer.
+    pub zeroes: usize,
+}
+
+/// `count` rotations of up to `max_turns` full turns plus a remainder, after
+/// exactly `zeroes` of which the dial points at 0.
+pub fn rotations(rng: &mut Rng, count: usize, zeroes: usize, max_turns: u64) -> Rotations {
+    let mut input = String::new();
+    let mut pos = 50;
+    for stop_at_zero in marks(rng, count, zeroes) {
+        let target = if stop_at_zero { 0 } else { rng.range(1, 99) };
+        let left = rng.chance(0.5);
+        let distance = if left {
+            (pos + 100 - target) % 100
+        } else {
+            (target + 100 - pos) % 100
+        };
+        let amount = distance + 100 * rng.range(0, max_turns);
+        writeln!(input, "{}{}", if left { 'L' } else { 'R' }, amount).unwrap();
+        pos = target;
+    }
+    Rotations { input, zeroes }
+}
+
+/// A day 2 list of ID ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdRanges {
+    pub input: String,
+    /// Every ID made of a sequence repeated twice, sorted; their sum is the
+    /// part 1 answer.
+    pub invalid: Vec<u64>,
+}
+
+/// `invalid` ranges around an ID made of a sequence of up to 5 digits repeated
+/// twice, and `decoys` ranges with none, in random order.
+///
+/// Ranges hold at most `max_width + 1` IDs, which keeps solvers that check
+/// every ID in them fast.
+pub fn id_ranges(rng: &mut Rng, invalid: usize, decoys: usize, max_width: u64) -> IdRanges {
+    // the k digit halves give 9 * 10^(k - 1) invalid IDs
+    assert!(invalid <= 99_999, "only 99999 IDs repeat a sequence twice");
+    let mut ranges = Vec::new();
+    let mut ids = Vec::new();
+    while ids.len() < invalid {
+        let k = rng.range(1, 5) as u32;
+        let half = rng.range(10u64.pow(k - 1), 10u64.pow(k) - 1);
+        let id = half * (10u64.pow(k) + 1);
+        if ids.contains(&id) {
+            continue;
+        }
+        // the nearest IDs with the same digit count repeating twice are
+        // 10^k + 1 away, and the ones with other digit counts further
+        let reach = (10u64.pow(k) / 2).saturating_sub(1).min(max_width / 2);
+        ranges.push((id - rng.range(0, reach), id + rng.range(0, reach)));
+        ids.push(id);
+    }
+    for _ in 0..decoys {
+        // an odd number of digits, so nothing in it repeats twice
+        let digits = 2 * rng.range(1, 4) as u32 + 1;
+        let (low, high) = (10u64.pow(digits - 1), 10u64.pow(digits) - 1);
+        let start = rng.range(low, high);
+        ranges.push((start, (start + rng.range(0, max_width)).min(high)));
+    }
+    rng.shuffle(&mut ranges);
+    ids.sort_unstable();
+
+    let input = ranges
+        .iter()
+        .map(|(start, end)| format!("{}-{}", start, end))
+        .collect::<Vec<_>>()
+        .join(",");
+    IdRanges {
+        input,
+        invalid: ids,
+    }
+}
+
+/// Day 3 battery banks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Banks {
+    pub input: String,
+    /// The largest joltage of each bank, which sum to the part 1 answer.
+    pub joltages: Vec<u64>,
+}
+
+/// `count` banks of `len` batteries. Each has its best pair at a random
+/// place, with the second battery last, and only smaller digits elsewhere.
+///
+/// Part 2 needs banks of at least 12 batteries.
+pub fn banks(rng: &mut Rng, count: usize, len: usize) -> Banks {
+    assert!(len >= 2, "a bank needs 2 batteries to make a pair");
+    let mut input = String::new();
+    let mut joltages = Vec::new();
+    for _ in 0..count {
+        let (first, second) = (rng.range(2, 9), rng.range(2, 9));
+        let smaller = first.min(second) - 1;
+        let mut bank: Vec<u64> = (0..len).map(|_| rng.range(1, smaller)).collect();
+        bank[rng.index(len - 1)] = first;
+        bank[len - 1] = second;
+        let digits: String = bank.iter().map(|d| d.to_string()).collect();
+        writeln!(input, "{}", digits).unwrap();
+        joltages.push(first * 10 + second);
+    }
+    Banks { input, joltages }
+}
+
+/// A day 4 grid of separate roll clusters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clusters {
+    pub input: String,
+    /// How many rolls have fewer than 4 neighbours, the part 1 answer.
+    pub accessible: usize,
+    /// How many rolls there are. Every one can be removed in the end, so
+    /// this is the part 2 answer.
+    pub rolls: usize,
+}
+
+/// `blocks` full 3 by 3 blocks of rolls and `singles` lone rolls, each in its
+/// own 3 by 3 tile, with an empty row and column between tiles.
+///
+/// Only a block's 4 corners have fewer than 4 neighbours; once they are
+/// removed, so are its edges and then its centre.
+pub fn clusters(rng: &mut Rng, blocks: usize, singles: usize) -> Clusters {
+    // about half the tiles stay empty
+    let mut side = 1;
+    while side * side < 2 * (blocks + singles) {
+        side += 1;
+    }
+    let mut tiles: Vec<usize> = (0..side * side).collect();
+    rng.shuffle(&mut tiles);
+
+    let size = 4 * side - 1;
+    let mut cells = vec![vec!['.'; size]; size];
+    for (n, &tile) in tiles[..blocks + singles].iter().enumerate() {
+        let (x, y) = (4 * (tile % side), 4 * (tile / side));
+        if n < blocks {
+            for row in &mut cells[y..y + 3] {
+                row[x..x + 3].fill('@');
+            }
+        } else {
+            cells[y + rng.index(3)][x + rng.index(3)] = '@';
+        }
+    }
+
+    let mut input = String::new();
+    for row in cells {
+        writeln!(input, "{}", row.into_iter().collect::<String>()).unwrap();
+    }
+    Clusters {
+        input,
+        accessible: 4 * blocks + singles,
+        rolls: 9 * blocks + singles,
+    }
+}
+
+/// A day 5 ingredient database and the available ingredients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Database {
+    pub input: String,
+    /// How many available ingredients are fresh, the part 1 answer.
+    pub fresh: usize,
+    /// How many IDs the database marks fresh, the part 2 answer.
+    pub fresh_ids: u128,
+}
+
+/// A database of `blocks` separate runs of fresh IDs, each split into up to 4
+/// overlapping ranges, then `fresh` available IDs inside them and `spoiled`
+/// between them, all in random order.
+pub fn database(rng: &mut Rng, blocks: usize, fresh: usize, spoiled: usize) -> Database {
+    assert!(blocks > 0 || fresh == 0, "fresh IDs need a block");
+    let mut ranges = Vec::new();
+    let mut runs = Vec::new();
+    let mut gaps = Vec::new();
+    let mut next = 1;
+    for _ in 0..blocks {
+        let start = next + rng.range(1, 1_000_000_000);
+        let end = start + rng.range(0, 10_000_000_000);
+        gaps.push((next, start - 1));
+        runs.push((start, end));
+
+        let pieces = rng.range(1, 4).min(end - start + 1);
+        let mut cuts: Vec<u64> = (1..pieces).map(|_| rng.range(start + 1, end)).collect();
+        cuts.push(start);
+        cuts.sort_unstable();
+        cuts.dedup();
+        for (i, &cut) in cuts.iter().enumerate() {
+            // each piece reaches at least to the next one, so they leave no hole
+            let reach = cuts.get(i + 1).map_or(end, |&next| next - 1);
+            let overlap = rng.range(0, (end - reach).min(1_000_000));
+            ranges.push((cut, reach + overlap));
+        }
+        next = end + 2;
+    }
+    gaps.push((next, next + 1_000_000_000));
+
+    let mut ids = Vec::new();
+    for _ in 0..fresh {
+        let (start, end) = runs[rng.index(runs.len())];
+        ids.push(rng.range(start, end));
+    }
+    for _ in 0..spoiled {
+        let (start, end) = gaps[rng.index(gaps.len())];
+        ids.push(rng.range(start, end));
+    }
+    rng.shuffle(&mut ranges);
+    rng.shuffle(&mut ids);
+
+    let mut input = String::new();
+    for (start, end) in ranges {
+        writeln!(input, "{}-{}", start, end).unwrap();
+    }
+    input.push('\n');
+    for id in ids {
+        writeln!(input, "{}", id).unwrap();
+    }
+    Database {
+        input,
+        fresh,
+        fresh_ids: runs.iter().map(|(s, e)| u128::from(e - s + 1)).sum(),
+    }
+}
+
+/// A day 6 worksheet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Worksheet {
+    pub input: String,
+    /// Every problem's numbers as written in its rows, and its operator.
+    pub problems: Vec<(Vec<u64>, char)>,
+    /// The sum of every problem's result, the part 1 answer.
+    pub total: u128,
+}
+
+/// `count` problems of `rows` numbers below 1000, each aligned left or right
+/// in its columns.
+pub fn worksheet(rng: &mut Rng, count: usize, rows: usize) -> Worksheet {
+    assert!(rows > 0, "a problem needs a number");
+    let mut lines = vec![String::new(); rows + 1];
+    let mut problems = Vec::new();
+    for i in 0..count {
+        let numbers: Vec<u64> = (0..rows).map(|_| rng.range(1, 999)).collect();
+        let operator = if rng.chance(0.5) { '+' } else { '*' };
+        let width = numbers.iter().map(|n| n.to_string().len()).max().unwrap();
+        let left = rng.chance(0.5);
+        let separator = if i + 1 < count { " " } else { "" };
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            if left {
+                write!(line, "{:<width$}{}", number, separator).unwrap();
+            } else {
+                write!(line, "{:>width$}{}", number, separator).unwrap();
+            }
+        }
+        write!(lines[rows], "{:<width$}{}", operator, separator).unwrap();
+        problems.push((numbers, operator));
+    }
+
+    let total = problems
+        .iter()
+        .map(|(numbers, operator)| {
+            let numbers = numbers.iter().map(|&n| u128::from(n));
+            match operator {
+                '+' => numbers.sum::<u128>(),
+                _ => numbers.product(),
+            }
+        })
+        .sum();
+    Worksheet {
+        input: lines.join("\n"),
+        problems,
+        total,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+    use crate::solution::Answer;
+
+    fn solve(day: u32, part: u32, input: &str) -> Answer {
+        registry::find(day, part, None)
+            .unwrap()
+            .solve(input)
+            .unwrap_or_else(|e| panic!("day {} part {}: {:#}\n{}", day, part, e, input))
+    }
+
+    #[test]
+    fn reproducible() {
+        let input = |seed| {
+            let mut rng = Rng::new(seed);
+            [
+                rotations(&mut rng, 50, 5, 3).input,
+                id_ranges(&mut rng, 5, 5, 100).input,
+                banks(&mut rng, 5, 20).input,
+                clusters(&mut rng, 5, 5).input,
+                database(&mut rng, 5, 10, 10).input,
+                worksheet(&mut rng, 10, 3).input,
+            ]
+        };
+        assert_eq!(input(7), input(7));
+        assert_ne!(input(7), input(8));
+    }
+
+    #[test]
+    fn rng() {
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| (3..=5).contains(&rng.range(3, 5))));
+        assert_eq!(rng.range(9, 9), 9);
+        rng.range(0, u64::MAX);
+        assert!((0..1000).all(|_| rng.index(3) < 3));
+        let heads = (0..1000).filter(|_| rng.chance(0.25)).count();
+        assert!((150..350).contains(&heads), "{}", heads);
+
+        let mut items: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn day1_zeroes() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let count = rng.index(200) + 1;
+            let zeroes = rng.index(count + 1);
+            let script = rotations(&mut rng, count, zeroes, 20);
+            assert_eq!(script.input.lines().count(), count);
+            assert_eq!(solve(1, 1, &script.input), zeroes.into());
+        }
+    }
+
+    #[test]
+    fn day2_invalid_ids() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let ranges = id_ranges(&mut rng, 10, 10, 1000);
+            assert_eq!(ranges.invalid.len(), 10);
+            assert_eq!(ranges.input.split(',').count(), 20);
+            let sum: u64 = ranges.invalid.iter().sum();
+            assert_eq!(solve(2, 1, &ranges.input), sum.into());
+        }
+        // small halves leave few candidates, but never repeat one
+        let ranges = id_ranges(&mut Rng::new(1), 200, 0, 10);
+        assert_eq!(ranges.invalid.len(), 200);
+    }
+
+    #[test]
+    fn day3_joltages() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let banks = banks(&mut rng, 30, 12 + seed as usize);
+            let sum: u64 = banks.joltages.iter().sum();
+            assert_eq!(solve(3, 1, &banks.input), sum.into());
+            solve(3, 2, &banks.input);
+        }
+        assert_eq!(banks(&mut Rng::new(1), 1, 2).joltages.len(), 1);
+    }
+
+    #[test]
+    fn day4_clusters() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let (blocks, singles) = (rng.index(30), rng.index(30));
+            let clusters = clusters(&mut rng, blocks, singles);
+            assert_eq!(clusters.input.matches('@').count(), clusters.rolls);
+            assert_eq!(solve(4, 1, &clusters.input), clusters.accessible.into());
+            assert_eq!(solve(4, 2, &clusters.input), clusters.rolls.into());
+        }
+        assert_eq!(clusters(&mut Rng::new(1), 0, 0).input, "...\n...\n...\n");
+        let block = clusters(&mut Rng::new(1), 1, 0);
+        assert_eq!((block.accessible, block.rolls), (4, 9));
+        assert_eq!(block.input.matches("@@@").count(), 3);
+    }
+
+    #[test]
+    fn day5_fresh() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let db = database(&mut rng, 1 + seed as usize, 50, 50);
+            assert_eq!(solve(5, 1, &db.input), db.fresh.into());
+            assert_eq!(solve(5, 2, &db.input), db.fresh_ids.into());
+        }
+    }
+
+    #[test]
+    fn day6_total() {
+        for seed in 0..20 {
+            let mut rng = Rng::new(seed);
+            let sheet = worksheet(&mut rng, 100, 1 + seed as usize % 4);
+            assert_eq!(sheet.problems.len(), 100);
+            assert_eq!(solve(6, 1, &sheet.input), sheet.total.into());
+            solve(6, 2, &sheet.input);
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Downloads puzzle inputs into `input/`, where the runners look for them.
+//!
+//! Every input is fetched at most once: after the first download it is read
+//! from the cache. Requests are spaced at least `min_interval` apart, also
+//! across runs, or as far as a rate limited answer's `Retry-After` asks. They
+//! identify this project in their `User-Agent` as the Advent of Code
+//! automation guidelines ask.
+
+use anyhow::{bail, Context};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable holding the `session` cookie of a logged-in user.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// Environment variable overriding [`DEFAULT_BASE_URL`], e.g. for a mock
+/// server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+/// File in the cache directory whose modification time is the last request.
+/// It holds the `Retry-After` seconds of that request's answer, if any.
+const LAST_REQUEST_FILE: &str = ".last-request";
+
+/// Longest `Retry-After` the next request waits out.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Fetches inputs for one year and one session, through an on-disk cache.
+///
+/// Not `Debug`, to keep the session token out of logs.
+#[derive(Clone)]
+pub struct Fetcher {
+    session: String,
+    year: u32,
+    base_url: String,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl Fetcher {
+    /// A fetcher for 2025 inputs with the default endpoint, caching in
+    /// `input/` and waiting 5 seconds between requests.
+    pub fn new(session: impl Into<String>) -> Self {
+        Fetcher {
+            session: session.into().trim().to_string(),
+            year: 2025,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cache_dir: PathBuf::from("input"),
+            min_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// A fetcher using the session in [`SESSION_VAR`], and the endpoint in
+    /// [`BASE_URL_VAR`] if set.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let fetcher = Fetcher::new(session_from_env()?);
+        Ok(match std::env::var(BASE_URL_VAR) {
+            Ok(base_url) => fetcher.with_base_url(base_url),
+            Err(_) => fetcher,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// `input/2025/day5.txt`, where `cargo aoc` also keeps inputs.
+    pub fn cache_path(&self, day: u32) -> PathBuf {
+        self.cache_dir
+            .join(self.year.to_string())
+            .join(format!("day{}.txt", day))
+    }
+
+    /// The input for `day`, downloaded only if it is not cached yet.
+    pub fn fetch(&self, day: u32) -> anyhow::Result<String> {
+        if !(1..=25).contains(&day) {
+            bail!("There is no day {}", day);
+        }
+        let path = self.cache_path(day);
+        if path.exists() {
+            return fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()));
+        }
+
+        let input = self.download(day)?;
+        write_atomically(&path, &input)?;
+        Ok(input)
+    }
+
+    fn download(&self, day: u32) -> anyhow::Result<String> {
+        if self.session.is_empty() {
+            bail!("The session token is empty");
+        }
+        wait_for_turn(&self.cache_dir, self.min_interval)?;
+
+        let url = format!("{}/{}/day/{}/input", self.base_url, self.year, day);
+        let response = ureq::get(&url)
+            .set("User-Agent", &user_agent())
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+
+        match response {
+            Ok(response) => {
+                let input = response
+                    .into_string()
+                    .with_context(|| format!("Failed to read the response from {}", url))?;
+                if input.is_empty() {
+                    bail!("{} returned an empty input", url);
+                }
+                Ok(input)
+            }
+            Err(ureq::Error::Status(404, _)) => {
+                bail!("Day {} of {} is not available yet", day, self.year)
+            }
+            Err(e) => Err(request_error(&self.cache_dir, &url, e)),
+        }
+    }
+}
+
+/// The session token in [`SESSION_VAR`].
+pub(crate) fn session_from_env() -> anyhow::Result<String> {
+    std::env::var(SESSION_VAR).with_context(|| {
+        format!(
+            "Set {} to your adventofcode.com session cookie",
+            SESSION_VAR
+        )
+    })
+}
+
+/// Explains a failed request to `url`, and has the next request made with
+/// `dir` as cache directory wait out the `Retry-After` it came with.
+pub(crate) fn request_error(dir: &Path, url: &str, error: ureq::Error) -> anyhow::Error {
+    if let ureq::Error::Status(429 | 503, response) = &error {
+        let seconds = response
+            .header("Retry-After")
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(seconds) = seconds {
+            // best effort: the error below matters more than a failed write
+            let _ = mark_request(&dir.join(LAST_REQUEST_FILE), &seconds.to_string());
+        }
+    }
+    match error {
+        ureq::Error::Status(status, response) => match (status, response.header("Retry-After")) {
+            (400 | 401 | 403, _) => anyhow::anyhow!(
+                "{} rejected the session token, log in again and update it",
+                url
+            ),
+            (429 | 503, Some(seconds)) => anyhow::anyhow!(
+                "{} is rate limiting requests, retry after {} seconds",
+                url,
+                seconds
+            ),
+            (429 | 503, None) => anyhow::anyhow!("{} is rate limiting requests", url),
+            (status, _) => anyhow::anyhow!("{} answered with status {}", url, status),
+        },
+        e => anyhow::Error::new(e).context(format!("Failed to request {}", url)),
+    }
+}
+
+/// Sleeps until `min_interval`, or the `Retry-After` of the last answer up to
+/// [`MAX_RETRY_AFTER`], has passed since the last request made with `dir` as
+/// cache directory, then records this one.
+pub(crate) fn wait_for_turn(dir: &Path, min_interval: Duration) -> anyhow::Result<()> {
+    let marker = dir.join(LAST_REQUEST_FILE);
+    let last = fs::metadata(&marker).and_then(|m| m.modified()).ok();
+    let retry_after = fs::read_to_string(&marker)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .map_or(Duration::ZERO, Duration::from_secs);
+    let interval = min_interval.max(retry_after.min(MAX_RETRY_AFTER));
+    if let Some(since) = last.and_then(|last| SystemTime::now().duration_since(last).ok()) {
+        if since < interval {
+            thread::sleep(interval - since);
+        }
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    mark_request(&marker, "").with_context(|| format!("Failed to write {}", marker.display()))
+}
+
+/// Writes the marker and stamps it with the clock's time: the time a write
+/// leaves can be a tick behind, which would shorten the next wait.
+fn mark_request(marker: &Path, contents: &str) -> std::io::Result<()> {
+    fs::write(marker, contents)?;
+    fs::File::options()
+        .write(true)
+        .open(marker)?
+        .set_modified(SystemTime::now())
+}
+
+/// `aoc2025/0.1.0 (Eduard Bondarenko <edbond@gmail.com>)`: who to contact
+/// about the traffic.
+pub fn user_agent() -> String {
+    format!(
+        "{}/{} ({})",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION"),
+        env!("CARGO_PKG_AUTHORS")
+    )
+}
+
+/// Writes through a temporary file so an interrupted download never leaves a
+/// truncated input in the cache.
+fn write_atomically(path: &Path, contents: &str) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let partial = path.with_extension("txt.partial");
+    fs::write(&partial, contents)
+        .with_context(|| format!("Failed to write {}", partial.display()))?;
+    fs::rename(&partial, path).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{response, stub_server};
+    use std::time::Instant;
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc2025-inputs-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn downloads_once_then_reads_the_cache() {
+        let (url, requests) = stub_server(vec![response("200 OK", "", "3-5\n\n4\n")]);
+        let dir = cache_dir("cache");
+        let fetcher = Fetcher::new("abc123\n")
+            .with_base_url(format!("{}/", url))
+            .with_cache_dir(&dir)
+            .with_min_interval(Duration::ZERO);
+
+        assert_eq!(fetcher.fetch(5).unwrap(), "3-5\n\n4\n");
+        let head = requests.recv().unwrap();
+        assert!(
+            head.starts_with("GET /2025/day/5/input HTTP/1.1\r\n"),
+            "{}",
+            head
+        );
+        assert!(head.contains("Cookie: session=abc123\r\n"), "{}", head);
+        assert!(
+            head.contains(&format!("User-Agent: {}\r\n", user_agent())),
+            "{}",
+            head
+        );
+
+        assert_eq!(
+            fs::read_to_string(dir.join("2025/day5.txt")).unwrap(),
+            "3-5\n\n4\n"
+        );
+        // the stub only answers once, so this must come from the cache
+        assert_eq!(fetcher.fetch(5).unwrap(), "3-5\n\n4\n");
+        assert!(requests.try_recv().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let (url, _requests) = stub_server(vec![
+            response(
+                "404 Not Found",
+                "",
+                "Please don't repeatedly request this endpoint",
+            ),
+            response("400 Bad Request", "", "Puzzle inputs differ by user"),
+            response("500 Internal Server Error", "", ""),
+            response("429 Too Many Requests", "Retry-After: 60\r\n", ""),
+        ]);
+        let dir = cache_dir("errors");
+        let fetcher = Fetcher::new("abc123")
+            .with_base_url(&url)
+            .with_cache_dir(&dir)
+            .with_min_interval(Duration::ZERO);
+
+        let err = |day| fetcher.fetch(day).err().unwrap().to_string();
+        assert_eq!(err(25), "Day 25 of 2025 is not available yet");
+        assert_eq!(
+            err(1),
+            format!(
+                "{}/2025/day/1/input rejected the session token, log in again and update it",
+                url
+            )
+        );
+        assert_eq!(
+            err(1),
+            format!("{}/2025/day/1/input answered with status 500", url)
+        );
+        assert_eq!(
+            err(1),
+            format!(
+                "{}/2025/day/1/input is rate limiting requests, retry after 60 seconds",
+                url
+            )
+        );
+        assert!(!fetcher.cache_path(1).exists());
+        assert!(!fetcher.cache_path(25).exists());
+
+        // no request is made for these
+        assert_eq!(err(26), "There is no day 26");
+        let fetcher = Fetcher::new(" ").with_cache_dir(&dir);
+        assert_eq!(
+            fetcher.fetch(2).err().unwrap().to_string(),
+            "The session token is empty"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn requests_are_spaced_out() {
+        let (url, requests) = stub_server(vec![
+            response("200 OK", "", "1\n"),
+            response("200 OK", "", "2\n"),
+        ]);
+        let dir = cache_dir("rate");
+        let fetcher = Fetcher::new("abc123")
+            .with_base_url(&url)
+            .with_cache_dir(&dir)
+            .with_min_interval(Duration::from_millis(300));
+
+        let start = Instant::now();
+        fetcher.fetch(1).unwrap();
+        // a separate fetcher, like a second run, still waits its turn
+        fetcher.clone().fetch(2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(requests.iter().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn retry_after_delays_the_next_request() {
+        let (url, requests) = stub_server(vec![
+            response("429 Too Many Requests", "Retry-After: 1\r\n", ""),
+            response("200 OK", "", "1\n"),
+        ]);
+        let dir = cache_dir("retry");
+        let fetcher = Fetcher::new("abc123")
+            .with_base_url(&url)
+            .with_cache_dir(&dir)
+            .with_min_interval(Duration::ZERO);
+
+        let start = Instant::now();
+        assert!(fetcher.fetch(1).is_err());
+        assert_eq!(fetcher.fetch(1).unwrap(), "1\n");
+        assert!(start.elapsed() >= Duration::from_secs(1));
+        assert_eq!(requests.iter().count(), 2);
+
+        // the wait is only for the answer that asked for it
+        let start = Instant::now();
+        wait_for_turn(&dir, Duration::ZERO).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -4,6 +4,21 @@ mod day4;
 mod day3;
 mod day2;
 mod day1;
+pub mod answers;
+#[cfg(test)]
+mod differential;
+pub mod error;
+#[cfg(test)]
+mod examples;
+pub mod fuzzing;
+pub mod gen;
+pub mod inputs;
+pub mod range_set;
+pub mod registry;
+pub mod solution;
+pub mod submit;
+#[cfg(test)]
+mod stub_server;
 use aoc_runner_derive::aoc_lib;
 
 aoc_lib! { year = 2025 }
@@ -0,0 +1,465 @@
+//! Runs the solutions without `cargo aoc`.
+//!
+//! ```text
+//! aoc2025 -d 5 [-p 1] [-n Report] [-i input.txt | -i -] [-t]
+//! aoc2025 --all [-t]
+//! aoc2025 --list
+//! aoc2025 verify [--answers answers.toml]
+//! aoc2025 submit -d 5 -p 1 [-i input.txt] [--answer 782]
+//! ```
+//!
+//! Input defaults to `input/2025/dayN.txt`, where `cargo aoc` keeps it.
+//! With `--fetch`, missing inputs are downloaded there first. `--record`
+//! stores the answers in `answers.toml`, and `verify` checks every stored
+//! answer still comes out the same. `submit` sends an answer to the site,
+//! and records it there too once it is accepted.
+
+use anyhow::{bail, Context};
+use aoc2025::answers::{self, AnswerRegistry, Entry};
+use aoc2025::inputs::Fetcher;
+use aoc2025::registry::{self, Solver};
+use aoc2025::solution::Answer;
+use aoc2025::submit::{Submitter, Verdict};
+use std::io::Read;
+use std::process::ExitCode;
+use std::time::Instant;
+
+const USAGE: &str = "\
+Usage: aoc2025 (-d DAY | --all | --list) [options]
+       aoc2025 verify [--answers PATH]
+       aoc2025 submit -d DAY -p PART [-i PATH] [-f] [--answer VALUE]
+
+  -d, --day DAY      Run one day
+  -a, --all          Run every day that has an input file
+  -p, --part PART    Run only part 1 or 2
+  -n, --name NAME    Run the named alternate solution instead of the main one
+  -i, --input PATH   Read the input from PATH, or stdin for '-'
+  -f, --fetch        Download missing inputs, using the session in AOC_SESSION
+  -t, --time         Print how long each part took, parsing included
+  -r, --record       Store the main solvers' answers as confirmed
+      --answers PATH Confirmed answers file, answers.toml by default
+      --answer VALUE Submit VALUE instead of solving the input
+  -l, --list         List every solver
+  -h, --help         Print this help";
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Options {
+    day: Option<u32>,
+    all: bool,
+    part: Option<u32>,
+    name: Option<String>,
+    input: Option<String>,
+    fetch: bool,
+    time: bool,
+    list: bool,
+    help: bool,
+    verify: bool,
+    record: bool,
+    answers: Option<String>,
+    submit: bool,
+    answer: Option<String>,
+}
+
+impl Options {
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Options> {
+        let mut options = Options::default();
+        let mut args = args.into_iter().peekable();
+        match args.peek().map(String::as_str) {
+            Some("verify") => options.verify = true,
+            Some("submit") => options.submit = true,
+            _ => {}
+        }
+        if options.verify || options.submit {
+            args.next();
+        }
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .with_context(|| format!("{} needs a value", arg))
+            };
+            match arg.as_str() {
+                "-d" | "--day" => {
+                    let day = value()?;
+                    options.day = Some(
+                        day.parse()
+                            .with_context(|| format!("Invalid day '{}'", day))?,
+                    );
+                }
+                "-p" | "--part" => {
+                    let part = value()?;
+                    options.part = match part.as_str() {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        _ => bail!("Invalid part '{}', expected 1 or 2", part),
+                    };
+                }
+                "-n" | "--name" => options.name = Some(value()?),
+                "-i" | "--input" => options.input = Some(value()?),
+                "-a" | "--all" => options.all = true,
+                "-f" | "--fetch" => options.fetch = true,
+                "-t" | "--time" => options.time = true,
+                "-r" | "--record" => options.record = true,
+                "--answers" => options.answers = Some(value()?),
+                "--answer" => options.answer = Some(value()?),
+                "-l" | "--list" => options.list = true,
+                "-h" | "--help" => options.help = true,
+                _ => bail!("Unknown argument '{}'", arg),
+            }
+        }
+
+        if options.help || options.list {
+            return Ok(options);
+        }
+        if options.verify {
+            if options.day.is_some() || options.all || options.record || options.input.is_some() {
+                bail!("verify only takes --answers");
+            }
+            return Ok(options);
+        }
+        if options.submit {
+            if options.day.is_none() || options.part.is_none() {
+                bail!("submit needs a day and a part");
+            }
+            if options.all || options.name.is_some() || options.record || options.time {
+                bail!("submit only takes -d, -p, -i, -f, --answer and --answers");
+            }
+            return Ok(options);
+        }
+        if options.answer.is_some() {
+            bail!("--answer is only for submit");
+        }
+        match (
+            options.record,
+            options.input.as_deref(),
+            options.name.is_some(),
+        ) {
+            (true, Some("-"), _) => bail!("--record needs an input file, not stdin"),
+            (true, _, true) => bail!("--record stores main solutions only, not --name"),
+            _ => {}
+        }
+        match (options.day, options.all) {
+            (None, false) => bail!("Pass a day with -d, or --all"),
+            (Some(_), true) => bail!("-d and --all cannot be used together"),
+            (None, true) if options.input.is_some() => {
+                bail!("--input needs a single day, not --all")
+            }
+            _ => {}
+        }
+        Ok(options)
+    }
+
+    fn answers_path(&self) -> &str {
+        self.answers.as_deref().unwrap_or(answers::DEFAULT_PATH)
+    }
+
+    /// The solvers to run for `day`, in part order.
+    fn solvers(&self, day: u32) -> anyhow::Result<Vec<Solver>> {
+        let parts = match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        };
+        parts
+            .into_iter()
+            .map(|part| {
+                registry::find(day, part, self.name.as_deref()).with_context(|| match &self.name {
+                    Some(name) => {
+                        format!("No solver for day {} part {} named '{}'", day, part, name)
+                    }
+                    None => format!("No solver for day {} part {}", day, part),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Reads a day's input from `path`, stdin for `-`, or the default location,
+/// downloading it there first if `fetch` is set.
+fn read_input(day: u32, path: Option<&str>, fetch: bool) -> anyhow::Result<String> {
+    match path {
+        Some("-") => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .context("Failed to read stdin")?;
+            Ok(input)
+        }
+        Some(path) => {
+            std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))
+        }
+        None if fetch => Fetcher::from_env()?.fetch(day),
+        None => {
+            let path = default_input(day);
+            std::fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path))
+        }
+    }
+}
+
+fn default_input(day: u32) -> String {
+    format!("input/2025/day{}.txt", day)
+}
+
+/// Runs the selected solvers for one day, printing each answer and adding
+/// it to `recorded` if given. Returns whether they all succeeded.
+fn run_day(
+    options: &Options,
+    day: u32,
+    mut recorded: Option<&mut AnswerRegistry>,
+) -> anyhow::Result<bool> {
+    let solvers = options.solvers(day)?;
+    let input = read_input(day, options.input.as_deref(), options.fetch)?;
+    let path = options.input.clone().unwrap_or_else(|| default_input(day));
+
+    let mut ok = true;
+    for solver in solvers {
+        let start = Instant::now();
+        let answer = solver.solve(&input);
+        let elapsed = start.elapsed();
+        match answer {
+            Ok(answer) => {
+                if options.time {
+                    println!("{}: {}  ({:.3?})", solver.label(), answer, elapsed);
+                } else {
+                    println!("{}: {}", solver.label(), answer);
+                }
+                if let Some(registry) = recorded.as_deref_mut() {
+                    let entry = Entry {
+                        day,
+                        part: solver.part,
+                        input: path.clone(),
+                        answer: answer.clone(),
+                    };
+                    match registry.insert(entry) {
+                        Some(previous) if previous != answer => {
+                            println!("  replaces the recorded answer {}", previous)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: {:#}", solver.label(), e);
+                ok = false;
+            }
+        }
+    }
+    Ok(ok)
+}
+
+fn run(options: &Options) -> anyhow::Result<bool> {
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(true);
+    }
+    if options.list {
+        for solver in registry::solvers() {
+            println!("{}", solver.label());
+        }
+        return Ok(true);
+    }
+
+    if options.verify {
+        return verify(options.answers_path());
+    }
+    if options.submit {
+        return submit(options);
+    }
+
+    let mut recorded = if options.record {
+        Some(AnswerRegistry::load(options.answers_path())?)
+    } else {
+        None
+    };
+    let ok = match options.day {
+        Some(day) => run_day(options, day, recorded.as_mut())?,
+        None => {
+            let mut ok = true;
+            for day in registry::days() {
+                if !options.fetch && !std::path::Path::new(&default_input(day)).exists() {
+                    println!("Day {}: no input at {}, skipped", day, default_input(day));
+                    continue;
+                }
+                ok &= run_day(options, day, recorded.as_mut())?;
+            }
+            ok
+        }
+    };
+    if let Some(recorded) = recorded {
+        recorded.save(options.answers_path())?;
+    }
+    Ok(ok)
+}
+
+/// Re-runs every confirmed answer in `path`. Returns whether they all match.
+fn verify(path: &str) -> anyhow::Result<bool> {
+    let registry = AnswerRegistry::load(path)?;
+    if registry.entries().is_empty() {
+        bail!(
+            "No confirmed answers in {}, record some with --record",
+            path
+        );
+    }
+    let checks = answers::verify(&registry);
+    for check in &checks {
+        println!("{}", check);
+    }
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    println!(
+        "{} of {} answers match",
+        checks.len() - failed,
+        checks.len()
+    );
+    Ok(failed == 0)
+}
+
+/// Submits the answer for one part, recording it as confirmed if it was
+/// solved from an input file and is correct. Returns whether it is correct.
+fn submit(options: &Options) -> anyhow::Result<bool> {
+    let (Some(day), Some(part)) = (options.day, options.part) else {
+        bail!("submit needs a day and a part");
+    };
+    let solver = options.solvers(day)?.remove(0);
+    let (answer, input) = match &options.answer {
+        Some(answer) => (answer.parse().unwrap_or_else(|e| match e {}), None),
+        None => {
+            let input = read_input(day, options.input.as_deref(), options.fetch)?;
+            let answer: Answer = solver.solve(&input).with_context(|| solver.label())?;
+            let path = match options.input.as_deref() {
+                Some("-") => None,
+                path => Some(path.map_or_else(|| default_input(day), str::to_string)),
+            };
+            (answer, path)
+        }
+    };
+
+    let verdict = Submitter::from_env()?.submit(day, part, &answer)?;
+    println!("{}: {}, {}", solver.label(), answer, verdict);
+    if verdict != Verdict::Correct {
+        return Ok(false);
+    }
+    if let Some(input) = input {
+        let mut registry = AnswerRegistry::load(options.answers_path())?;
+        registry.insert(Entry {
+            day,
+            part,
+            input,
+            answer,
+        });
+        registry.save(options.answers_path())?;
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{:#}\n\n{}", e, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> anyhow::Result<Options> {
+        Options::parse(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn options() {
+        assert_eq!(
+            parse("-d 5 -p 2 -n Coverage -i - -t").unwrap(),
+            Options {
+                day: Some(5),
+                part: Some(2),
+                name: Some("Coverage".into()),
+                input: Some("-".into()),
+                time: true,
+                ..Options::default()
+            }
+        );
+        assert!(parse("--all").unwrap().all);
+        assert!(parse("--list").unwrap().list);
+        assert!(parse("--all --fetch").unwrap().fetch);
+        assert_eq!(
+            parse("verify --answers a.toml").unwrap(),
+            Options {
+                verify: true,
+                answers: Some("a.toml".into()),
+                ..Options::default()
+            }
+        );
+        assert_eq!(parse("verify").unwrap().answers_path(), "answers.toml");
+        assert!(parse("--all --record").unwrap().record);
+        assert_eq!(
+            parse("submit -d 5 -p 2 --answer 14").unwrap(),
+            Options {
+                submit: true,
+                day: Some(5),
+                part: Some(2),
+                answer: Some("14".into()),
+                ..Options::default()
+            }
+        );
+
+        let err = |args: &str| parse(args).err().unwrap().to_string();
+        assert_eq!(err(""), "Pass a day with -d, or --all");
+        assert_eq!(err("-d 1 --all"), "-d and --all cannot be used together");
+        assert_eq!(
+            err("--all -i x.txt"),
+            "--input needs a single day, not --all"
+        );
+        assert_eq!(err("-d"), "-d needs a value");
+        assert_eq!(err("-d one"), "Invalid day 'one'");
+        assert_eq!(err("-d 1 -p 3"), "Invalid part '3', expected 1 or 2");
+        assert_eq!(err("-x"), "Unknown argument '-x'");
+        assert_eq!(err("verify -d 1"), "verify only takes --answers");
+        assert_eq!(err("submit -d 1"), "submit needs a day and a part");
+        assert_eq!(
+            err("submit -d 1 -p 1 -t"),
+            "submit only takes -d, -p, -i, -f, --answer and --answers"
+        );
+        assert_eq!(err("-d 1 --answer 3"), "--answer is only for submit");
+        assert_eq!(err("-d 1 verify"), "Unknown argument 'verify'");
+        assert_eq!(
+            err("-d 1 -i - -r"),
+            "--record needs an input file, not stdin"
+        );
+        assert_eq!(
+            err("-d 5 -n Stream -r"),
+            "--record stores main solutions only, not --name"
+        );
+    }
+
+    #[test]
+    fn solver_selection() {
+        let labels = |args: &str, day: u32| {
+            parse(args)
+                .unwrap()
+                .solvers(day)
+                .map(|solvers| solvers.iter().map(Solver::label).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            labels("-d 1", 1).unwrap(),
+            ["Day 1 - Part 1", "Day 1 - Part 2"]
+        );
+        assert_eq!(
+            labels("-d 5 -n stream", 5).unwrap(),
+            ["Day 5 - Part 1 - Stream", "Day 5 - Part 2 - Stream"]
+        );
+        assert_eq!(
+            labels("-d 5 -n Report", 5).err().unwrap().to_string(),
+            "No solver for day 5 part 2 named 'Report'"
+        );
+    }
+}
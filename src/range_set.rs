@@ -0,0 +1,510 @@
+//! Sets of integers stored as sorted, disjoint, inclusive ranges.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+/// Integer types that can be stored in a [`RangeSet`].
+pub trait Integer: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    /// Returns `self + 1`, or `None` at the top of the domain.
+    fn checked_succ(self) -> Option<Self>;
+
+    /// Returns `self - 1`, or `None` at the bottom of the domain.
+    fn checked_pred(self) -> Option<Self>;
+
+    /// Number of values in the inclusive range `[start, end]`.
+    ///
+    /// Returned as `u128` so the full `u64` domain (2^64 values) still fits.
+    fn span(start: Self, end: Self) -> u128;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn checked_succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn checked_pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn span(start: Self, end: Self) -> u128 {
+                    (end - start) as u128 + 1
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(u8, u16, u32, u64, usize);
+
+/// A set of integers kept as a sorted list of disjoint, inclusive ranges.
+///
+/// Overlapping and adjacent ranges are coalesced on insert, so `3-5` and
+/// `6-8` are stored as a single range `3-8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: Vec::new() }
+    }
+}
+
+/// Returns true if a range ending at `a_end` and a later range starting at
+/// `b_start` overlap or touch, so they can be stored as one range.
+fn joinable<T: Integer>(a_end: T, b_start: T) -> bool {
+    match a_end.checked_succ() {
+        Some(next) => b_start <= next,
+        None => true,
+    }
+}
+
+impl<T: Integer> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a set from ranges in any order, sorting and merging them.
+    ///
+    /// Ranges with `start > end` are empty and ignored.
+    pub fn from_ranges<I>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = (T, T)>,
+    {
+        let mut sorted: Vec<(T, T)> = ranges
+            .into_iter()
+            .filter(|(start, end)| start <= end)
+            .collect();
+        sorted.sort_unstable();
+
+        // fold over sorted ranges and extend the last one while the next overlaps it
+        let ranges = sorted
+            .into_iter()
+            .fold(Vec::<(T, T)>::new(), |mut acc, (start, end)| {
+                match acc.last_mut() {
+                    Some(current) if joinable(current.1, start) => {
+                        current.1 = current.1.max(end);
+                    }
+                    _ => acc.push((start, end)),
+                }
+                acc
+            });
+
+        RangeSet { ranges }
+    }
+
+    /// Adds every value in `[start, end]` to the set.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        // first range that could touch [start, end]
+        let lo = self.ranges.partition_point(|&(_, e)| !joinable(e, start));
+        // first range that starts strictly after end and does not touch it
+        let hi = self.ranges.partition_point(|&(s, _)| joinable(end, s));
+
+        let (mut new_start, mut new_end) = (start, end);
+        if lo < hi {
+            new_start = new_start.min(self.ranges[lo].0);
+            new_end = new_end.max(self.ranges[hi - 1].1);
+        }
+        self.ranges.splice(lo..hi, [(new_start, new_end)]);
+    }
+
+    /// Removes every value in `[start, end]` from the set.
+    pub fn remove(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        let lo = self.ranges.partition_point(|&(_, e)| e < start);
+        let hi = self.ranges.partition_point(|&(s, _)| s <= end);
+        if lo >= hi {
+            return;
+        }
+
+        let mut kept = Vec::with_capacity(2);
+        let (first_start, _) = self.ranges[lo];
+        if first_start < start {
+            kept.push((first_start, start.checked_pred().unwrap()));
+        }
+        let (_, last_end) = self.ranges[hi - 1];
+        if last_end > end {
+            kept.push((end.checked_succ().unwrap(), last_end));
+        }
+        self.ranges.splice(lo..hi, kept);
+    }
+
+    /// Returns true if `value` is in the set.
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < value);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// Values in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        Self::from_ranges(self.iter().chain(other.iter()))
+    }
+
+    /// Values in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // advance whichever range finishes first
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { ranges }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for (start, end) in other.iter() {
+            result.remove(start, end);
+        }
+        result
+    }
+
+    /// Values in `[lo, hi]` that are not in the set.
+    pub fn complement(&self, lo: T, hi: T) -> Self {
+        let mut bounds = RangeSet::new();
+        bounds.insert(lo, hi);
+        bounds.difference(self)
+    }
+
+    /// Total number of values in the set.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| T::span(start, end))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterates over the disjoint ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().copied()
+    }
+
+    /// The disjoint ranges in ascending order.
+    pub fn ranges(&self) -> &[(T, T)] {
+        &self.ranges
+    }
+}
+
+impl<T: Integer> FromIterator<(T, T)> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        Self::from_ranges(iter)
+    }
+}
+
+/// A range set for interleaved updates and queries.
+///
+/// [`RangeSet`] keeps its ranges in a `Vec`, so each `insert` or `remove`
+/// shifts the tail of the list. This keeps them in a `BTreeMap` keyed by
+/// range start instead: every operation is `O(log n)` amortised, because
+/// each range merged or split away was added by an earlier insert. The
+/// total length is maintained as ranges change, so `len` is `O(1)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DynamicRangeSet<T> {
+    ranges: BTreeMap<T, T>,
+    len: u128,
+}
+
+impl<T> Default for DynamicRangeSet<T> {
+    fn default() -> Self {
+        DynamicRangeSet {
+            ranges: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T: Integer> DynamicRangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add_range(&mut self, start: T, end: T) {
+        self.ranges.insert(start, end);
+        self.len += T::span(start, end);
+    }
+
+    fn take_range(&mut self, start: T) -> T {
+        let end = self.ranges.remove(&start).unwrap();
+        self.len -= T::span(start, end);
+        end
+    }
+
+    /// Adds every value in `[start, end]` to the set.
+    pub fn insert(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        let (mut new_start, mut new_end) = (start, end);
+
+        // the range starting at or before `start` may overlap or touch it
+        if let Some((&s, &e)) = self.ranges.range(..=start).next_back() {
+            if joinable(e, start) {
+                self.take_range(s);
+                new_start = s;
+                new_end = new_end.max(e);
+            }
+        }
+
+        // swallow every later range that starts inside or right after the new one
+        loop {
+            let next = match new_end.checked_succ() {
+                Some(limit) => self.ranges.range(new_start..=limit).next(),
+                None => self.ranges.range(new_start..).next(),
+            };
+            match next {
+                Some((&s, _)) => {
+                    let e = self.take_range(s);
+                    new_end = new_end.max(e);
+                }
+                None => break,
+            }
+        }
+
+        self.add_range(new_start, new_end);
+    }
+
+    /// Removes every value in `[start, end]` from the set.
+    pub fn remove(&mut self, start: T, end: T) {
+        if start > end {
+            return;
+        }
+
+        // a range starting before `start` may reach into the removed span
+        if let Some((&s, &e)) = self.ranges.range(..start).next_back() {
+            if e >= start {
+                self.take_range(s);
+                self.add_range(s, start.checked_pred().unwrap());
+                if e > end {
+                    self.add_range(end.checked_succ().unwrap(), e);
+                }
+            }
+        }
+
+        while let Some((&s, _)) = self.ranges.range(start..=end).next() {
+            let e = self.take_range(s);
+            if e > end {
+                self.add_range(end.checked_succ().unwrap(), e);
+            }
+        }
+    }
+
+    /// Returns true if `value` is in the set.
+    pub fn contains(&self, value: T) -> bool {
+        self.ranges
+            .range(..=value)
+            .next_back()
+            .is_some_and(|(_, &end)| value <= end)
+    }
+
+    /// Total number of values in the set.
+    pub fn len(&self) -> u128 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Iterates over the disjoint ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = (T, T)> + '_ {
+        self.ranges.iter().map(|(&start, &end)| (start, end))
+    }
+}
+
+impl<T: Integer> From<&DynamicRangeSet<T>> for RangeSet<T> {
+    fn from(set: &DynamicRangeSet<T>) -> Self {
+        RangeSet {
+            ranges: set.iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+
+    fn set(ranges: &[(u64, u64)]) -> RangeSet<u64> {
+        ranges.iter().copied().collect()
+    }
+
+    #[test]
+    fn from_ranges_merges_overlapping_and_adjacent() {
+        assert_eq!(
+            set(&[(10, 14), (3, 5), (16, 20), (12, 18)]).ranges(),
+            &[(3, 5), (10, 20)]
+        );
+        assert_eq!(set(&[(3, 5), (6, 8)]).ranges(), &[(3, 8)]);
+        assert_eq!(set(&[(5, 3)]).ranges(), &[]);
+    }
+
+    #[test]
+    fn empty_set() {
+        let empty = RangeSet::<u64>::new();
+        assert!(empty.is_empty());
+        assert_eq!(empty.len(), 0);
+        assert!(!empty.contains(0));
+    }
+
+    #[test]
+    fn insert() {
+        let mut s = set(&[(3, 5), (10, 14)]);
+        s.insert(7, 8);
+        assert_eq!(s.ranges(), &[(3, 5), (7, 8), (10, 14)]);
+        s.insert(6, 6);
+        assert_eq!(s.ranges(), &[(3, 8), (10, 14)]);
+        s.insert(0, 20);
+        assert_eq!(s.ranges(), &[(0, 20)]);
+        s.insert(u64::MAX - 1, u64::MAX);
+        assert_eq!(s.ranges(), &[(0, 20), (u64::MAX - 1, u64::MAX)]);
+    }
+
+    #[test]
+    fn remove() {
+        let mut s = set(&[(3, 5), (10, 20)]);
+        s.remove(15, 16);
+        assert_eq!(s.ranges(), &[(3, 5), (10, 14), (17, 20)]);
+        s.remove(4, 12);
+        assert_eq!(s.ranges(), &[(3, 3), (13, 14), (17, 20)]);
+        s.remove(0, 100);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn contains() {
+        let s = set(&[(3, 5), (10, 14), (16, 20), (12, 18)]);
+        let fresh: Vec<u64> = [1, 5, 8, 11, 17, 32]
+            .into_iter()
+            .filter(|&id| s.contains(id))
+            .collect();
+        assert_eq!(fresh, vec![5, 11, 17]);
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a = set(&[(1, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+
+        assert_eq!(a.union(&b).ranges(), &[(1, 30)]);
+        assert_eq!(a.intersection(&b).ranges(), &[(5, 10), (20, 25)]);
+        assert_eq!(a.difference(&b).ranges(), &[(1, 4), (26, 30)]);
+        assert_eq!(b.difference(&a).ranges(), &[(11, 19)]);
+        assert_eq!(a.complement(0, 40).ranges(), &[(0, 0), (11, 19), (31, 40)]);
+    }
+
+    #[test]
+    fn len() {
+        assert_eq!(set(&[(3, 5), (10, 14), (16, 20), (12, 18)]).len(), 14);
+    }
+
+    #[test]
+    fn len_full_domain() {
+        assert_eq!(set(&[(0, u64::MAX)]).len(), 1 << 64);
+        assert_eq!(set(&[(u64::MAX, u64::MAX)]).len(), 1);
+        assert_eq!(set(&[(0, 0), (1, u64::MAX)]).ranges(), &[(0, u64::MAX)]);
+        assert_eq!(set(&[(0, u64::MAX), (5, 10)]).len(), 1 << 64);
+        assert_eq!(RangeSet::<u8>::from_ranges([(0, 255)]).len(), 256);
+    }
+
+    #[test]
+    fn complement_full_domain() {
+        let s = set(&[(0, 9), (u64::MAX - 9, u64::MAX)]);
+        assert_eq!(s.complement(0, u64::MAX).ranges(), &[(10, u64::MAX - 10)]);
+        assert!(set(&[(0, u64::MAX)]).complement(0, u64::MAX).is_empty());
+    }
+
+    #[test]
+    fn dynamic_insert_remove() {
+        let mut s = DynamicRangeSet::<u64>::new();
+        s.insert(10, 20);
+        s.insert(30, 40);
+        s.insert(21, 29);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![(10, 40)]);
+        assert_eq!(s.len(), 31);
+
+        s.remove(15, 16);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![(10, 14), (17, 40)]);
+        assert_eq!(s.len(), 29);
+        assert!(s.contains(17));
+        assert!(!s.contains(16));
+
+        s.remove(0, 100);
+        assert!(s.is_empty());
+        assert_eq!(s.len(), 0);
+    }
+
+    #[test]
+    fn dynamic_full_domain() {
+        let mut s = DynamicRangeSet::new();
+        s.insert(u64::MAX, u64::MAX);
+        s.insert(0, u64::MAX - 1);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![(0, u64::MAX)]);
+        assert_eq!(s.len(), 1 << 64);
+
+        s.remove(0, 0);
+        s.remove(u64::MAX, u64::MAX);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![(1, u64::MAX - 1)]);
+    }
+
+    #[test]
+    fn dynamic_matches_range_set() {
+        let mut rng = Rng::new(12345);
+        let mut next = |bound: u64| rng.range(0, bound - 1);
+
+        let mut dynamic = DynamicRangeSet::new();
+        let mut reference = RangeSet::new();
+        for _ in 0..2000 {
+            let start = next(200);
+            let end = start + next(20);
+            if next(3) == 0 {
+                dynamic.remove(start, end);
+                reference.remove(start, end);
+            } else {
+                dynamic.insert(start, end);
+                reference.insert(start, end);
+            }
+            assert_eq!(RangeSet::from(&dynamic), reference);
+            assert_eq!(dynamic.len(), reference.len());
+            let probe = next(230);
+            assert_eq!(dynamic.contains(probe), reference.contains(probe));
+        }
+    }
+}
@@ -0,0 +1,201 @@
+//! Every solver in the library, so a runner can find them without
+//! `cargo aoc`.
+//!
+//! Both parts of every day come from its [`Solution`]. Days with named
+//! alternates, the ones `#[aoc(dayN, partX, Name)]` exposes to `cargo aoc`,
+//! also list them in an `ALTERNATES` table.
+//!
+//! [`generators`] splits the main solvers into parsing and the two parts, so
+//! they can be timed separately.
+
+use crate::day1::{self, Day1};
+use crate::day2::{self, Day2};
+use crate::day3::{self, Day3};
+use crate::day4::{self, Day4};
+use crate::day5::{self, Day5};
+use crate::day6::{self, Day6};
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+
+/// One part of one day, run on raw puzzle input.
+#[derive(Debug, Clone, Copy)]
+pub struct Solver {
+    pub day: u32,
+    pub part: u32,
+    /// Name of an alternate solution, `None` for the main one.
+    pub name: Option<&'static str>,
+    /// Parses the input and solves the part.
+    pub run: fn(&str) -> anyhow::Result<Answer>,
+}
+
+impl Solver {
+    /// `Day 5 - Part 1` or `Day 5 - Part 1 - Report`.
+    pub fn label(&self) -> String {
+        match self.name {
+            Some(name) => format!("Day {} - Part {} - {}", self.day, self.part, name),
+            None => format!("Day {} - Part {}", self.day, self.part),
+        }
+    }
+
+    /// Runs the solver on an input as read from a file.
+    pub fn solve(&self, input: &str) -> anyhow::Result<Answer> {
+        (self.run)(trim(input))
+    }
+}
+
+/// Inputs saved from a browser often end in a newline the parsers don't
+/// expect.
+fn trim(input: &str) -> &str {
+    input.trim_end_matches(['\n', '\r'])
+}
+
+/// A day's parsed input, ready for either part.
+pub trait Parsed {
+    fn part1(&self) -> anyhow::Result<Answer>;
+
+    fn part2(&self) -> anyhow::Result<Answer>;
+}
+
+struct Input<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for Input<S> {
+    fn part1(&self) -> anyhow::Result<Answer> {
+        S::part1(&self.0)
+    }
+
+    fn part2(&self) -> anyhow::Result<Answer> {
+        S::part2(&self.0)
+    }
+}
+
+/// The parser of one day, whose output runs both main parts.
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    pub day: u32,
+    parse: fn(&str) -> Result<Box<dyn Parsed>, ParseError>,
+}
+
+impl Generator {
+    /// Parses an input as read from a file.
+    pub fn parse(&self, input: &str) -> Result<Box<dyn Parsed>, ParseError> {
+        (self.parse)(trim(input))
+    }
+}
+
+fn generator<S: Solution + 'static>() -> Generator {
+    Generator {
+        day: S::DAY,
+        parse: |input| Ok(Box::new(Input::<S>(S::parse(input)?))),
+    }
+}
+
+/// The generator of every day, in day order.
+pub fn generators() -> Vec<Generator> {
+    vec![
+        generator::<Day1>(),
+        generator::<Day2>(),
+        generator::<Day3>(),
+        generator::<Day4>(),
+        generator::<Day5>(),
+        generator::<Day6>(),
+    ]
+}
+
+/// The two main solvers of a day.
+fn parts<S: Solution>() -> [Solver; 2] {
+    [
+        Solver {
+            day: S::DAY,
+            part: 1,
+            name: None,
+            run: |input| S::part1(&S::parse(input)?),
+        },
+        Solver {
+            day: S::DAY,
+            part: 2,
+            name: None,
+            run: |input| S::part2(&S::parse(input)?),
+        },
+    ]
+}
+
+/// All solvers, ordered by day and part.
+pub fn solvers() -> Vec<Solver> {
+    let mut solvers: Vec<Solver> = [
+        parts::<Day1>(),
+        parts::<Day2>(),
+        parts::<Day3>(),
+        parts::<Day4>(),
+        parts::<Day5>(),
+        parts::<Day6>(),
+    ]
+    .concat();
+    solvers.extend_from_slice(day1::ALTERNATES);
+    solvers.extend_from_slice(day2::ALTERNATES);
+    solvers.extend_from_slice(day3::ALTERNATES);
+    solvers.extend_from_slice(day4::ALTERNATES);
+    solvers.extend_from_slice(day5::ALTERNATES);
+    solvers.extend_from_slice(day6::ALTERNATES);
+    // stable, so alternates stay after the main solution in listed order
+    solvers.sort_by_key(|s| (s.day, s.part, s.name.is_some()));
+    solvers
+}
+
+/// The days that have at least one solver.
+pub fn days() -> Vec<u32> {
+    let mut days: Vec<u32> = solvers().iter().map(|s| s.day).collect();
+    days.dedup();
+    days
+}
+
+/// Looks up a solver; `name` is matched case-insensitively.
+pub fn find(day: u32, part: u32, name: Option<&str>) -> Option<Solver> {
+    solvers().into_iter().find(|s| {
+        s.day == day
+            && s.part == part
+            && match (s.name, name) {
+                (None, None) => true,
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
+            }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_day_has_both_parts() {
+        assert_eq!(days(), vec![1, 2, 3, 4, 5, 6]);
+        for day in days() {
+            for part in [1, 2] {
+                assert!(find(day, part, None).is_some(), "day {} part {}", day, part);
+            }
+        }
+    }
+
+    #[test]
+    fn lookup() {
+        let solver = find(5, 1, Some("report")).unwrap();
+        assert_eq!(solver.label(), "Day 5 - Part 1 - Report");
+        assert!(find(5, 1, Some("missing")).is_none());
+        assert!(find(7, 1, None).is_none());
+
+        let solver = find(6, 1, None).unwrap();
+        assert_eq!(solver.label(), "Day 6 - Part 1");
+        assert_eq!((solver.run)("1 2\n3 4\n+ *").unwrap(), Answer::Integer(12));
+        assert!((solver.run)("1 2\n3 4").is_err());
+    }
+
+    #[test]
+    fn generators_match_solvers() {
+        let days: Vec<u32> = generators().iter().map(|g| g.day).collect();
+        assert_eq!(days, self::days());
+
+        let parsed = generators()[5].parse("1 2\n3 4\n+ *\n").unwrap();
+        assert_eq!(parsed.part1().unwrap(), Answer::Integer(12));
+        assert_eq!(parsed.part2().unwrap(), Answer::Integer(37));
+        assert_eq!(generators()[5].parse("1 2\n3 4").err().unwrap().day, 6);
+    }
+}
@@ -0,0 +1,206 @@
+//! The interface every day implements, and the answers it produces.
+
+use crate::error::ParseError;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// One day's puzzle: a parser and the two parts that share its output.
+pub trait Solution {
+    /// Day of December the puzzle is released on.
+    const DAY: u32;
+
+    /// What `parse` turns the puzzle input into.
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> anyhow::Result<Answer>;
+
+    fn part2(input: &Self::Input) -> anyhow::Result<Answer>;
+}
+
+/// The answer to one part.
+///
+/// Integers are kept in `Integer` whenever they fit, so two answers with the
+/// same value always compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i128),
+    /// An integer outside the range of `i128`.
+    BigInteger(BigInt),
+    /// Anything that is not a number, such as a report.
+    Text(String),
+}
+
+impl Answer {
+    /// The sum of two integer answers, `None` if either is text.
+    pub fn checked_add(&self, other: &Answer) -> Option<Answer> {
+        match (self, other) {
+            (Answer::Integer(a), Answer::Integer(b)) => Some(match a.checked_add(*b) {
+                Some(sum) => Answer::Integer(sum),
+                None => (BigInt::from(*a) + *b).into(),
+            }),
+            (Answer::Text(_), _) | (_, Answer::Text(_)) => None,
+            (a, b) => Some((a.to_big()? + b.to_big()?).into()),
+        }
+    }
+
+    fn to_big(&self) -> Option<BigInt> {
+        match self {
+            Answer::Integer(n) => Some(BigInt::from(*n)),
+            Answer::BigInteger(n) => Some(n.clone()),
+            Answer::Text(_) => None,
+        }
+    }
+
+    /// A JSON value: a number for integers, a string for text.
+    pub fn to_json(&self) -> String {
+        match self {
+            Answer::Integer(_) | Answer::BigInteger(_) => self.to_string(),
+            Answer::Text(text) => {
+                let mut json = String::from("\"");
+                for c in text.chars() {
+                    match c {
+                        '"' => json += "\\\"",
+                        '\\' => json += "\\\\",
+                        '\n' => json += "\\n",
+                        '\r' => json += "\\r",
+                        '\t' => json += "\\t",
+                        c if (c as u32) < 0x20 => json += &format!("\\u{:04x}", c as u32),
+                        c => json.push(c),
+                    }
+                }
+                json.push('"');
+                json
+            }
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{}", n),
+            Answer::BigInteger(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(n.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i32, i64, u32, u64);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Integer(n as i128)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        BigInt::from(n).into()
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(n: BigInt) -> Self {
+        match n.to_i128() {
+            Some(n) => Answer::Integer(n),
+            None => Answer::BigInteger(n),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// Reads back a displayed answer: integers of any size, otherwise text.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.trim().parse::<BigInt>() {
+            Ok(n) => n.into(),
+            Err(_) => Answer::Text(s.to_string()),
+        })
+    }
+}
+
+/// `values` written out and separated by `sep`, for the reports some
+/// alternates print.
+pub(crate) fn join<T: fmt::Display>(values: &[T], sep: &str) -> String {
+    values
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(sep)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_compare_by_value() {
+        assert_eq!(Answer::from(42u64), Answer::from(42usize));
+        assert_eq!(Answer::from(42u128), Answer::Integer(42));
+        assert_eq!(Answer::from(BigInt::from(-7)), Answer::Integer(-7));
+        assert!(matches!(Answer::from(u128::MAX), Answer::BigInteger(_)));
+        assert_ne!(Answer::from(42u64), Answer::from("42"));
+    }
+
+    #[test]
+    fn round_trip() {
+        for answer in [
+            Answer::from(0u64),
+            Answer::from(-12i32),
+            Answer::from(u128::MAX),
+            Answer::from(BigInt::from(u128::MAX) * 1000),
+            Answer::from("\nreport\n"),
+        ] {
+            assert_eq!(answer.to_string().parse::<Answer>().unwrap(), answer);
+        }
+        assert_eq!(" 17\n".parse::<Answer>().unwrap(), Answer::Integer(17));
+    }
+
+    #[test]
+    fn sums() {
+        let max = Answer::Integer(i128::MAX);
+        let sum = max.checked_add(&Answer::from(1u64)).unwrap();
+        assert_eq!(sum.to_string(), "170141183460469231731687303715884105728");
+        assert_eq!(
+            sum.checked_add(&Answer::Integer(-1)).unwrap(),
+            Answer::Integer(i128::MAX)
+        );
+        assert_eq!(max.checked_add(&Answer::from("x")), None);
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(Answer::from(u128::MAX).to_json(), u128::MAX.to_string());
+        assert_eq!(Answer::from("a \"b\"\n\\").to_json(), r#""a \"b\"\n\\""#);
+    }
+}
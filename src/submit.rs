@@ -0,0 +1,668 @@
+//! Submits answers to adventofcode.com and keeps a log of every reply.
+//!
+//! The log, `input/2025/submissions.log`, lets [`Submitter::submit`] refuse
+//! to send an answer that cannot be right: one already rejected, one outside
+//! the bounds of earlier "too high" and "too low" replies, or a different one
+//! for a part already solved. It also keeps the wait the site asks for after
+//! a wrong answer, so a second run does not submit too early either.
+
+use crate::inputs::{self, DEFAULT_BASE_URL};
+use crate::solution::Answer;
+use anyhow::{bail, Context};
+use num_bigint::BigInt;
+use regex::Regex;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint.
+    Wrong,
+    /// The part was already solved, so the answer was not checked.
+    AlreadySolved,
+    /// Submitted too soon after the previous answer, so it was not checked.
+    Wait(Duration),
+}
+
+impl Verdict {
+    /// Whether the answer was checked and found wrong.
+    pub fn is_wrong(self) -> bool {
+        matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+            Verdict::Wait(wait) => write!(f, "wait {}s", wait.as_secs()),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Verdict> {
+        Ok(match s {
+            "correct" => Verdict::Correct,
+            "too high" => Verdict::TooHigh,
+            "too low" => Verdict::TooLow,
+            "wrong" => Verdict::Wrong,
+            "already solved" => Verdict::AlreadySolved,
+            _ => {
+                let seconds = s
+                    .strip_prefix("wait ")
+                    .and_then(|s| s.strip_suffix('s'))
+                    .and_then(|s| s.parse().ok())
+                    .with_context(|| format!("Unknown verdict '{}'", s))?;
+                Verdict::Wait(Duration::from_secs(seconds))
+            }
+        })
+    }
+}
+
+/// The verdict in a reply, and how long to wait before the next answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reply {
+    pub verdict: Verdict,
+    pub cooldown: Duration,
+}
+
+/// Reads the verdict out of the HTML page answering a submission.
+pub fn parse_reply(html: &str) -> anyhow::Result<Reply> {
+    // the message is the page's only <article>
+    let text = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+    let text = Regex::new(r"<[^>]*>").unwrap().replace_all(text, "");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let minutes = Regex::new(r"(?i)wait (one|\d+) minutes? before trying again").unwrap();
+    let cooldown = match minutes.captures(&text) {
+        Some(c) if &c[1] == "one" => Duration::from_secs(60),
+        Some(c) => Duration::from_secs(c[1].parse::<u64>()? * 60),
+        None => Duration::ZERO,
+    };
+    let reply = |verdict| Ok(Reply { verdict, cooldown });
+
+    if text.contains("That's the right answer") {
+        return reply(Verdict::Correct);
+    }
+    if text.contains("That's not the right answer") {
+        return if text.contains("your answer is too high") {
+            reply(Verdict::TooHigh)
+        } else if text.contains("your answer is too low") {
+            reply(Verdict::TooLow)
+        } else {
+            reply(Verdict::Wrong)
+        };
+    }
+    if text.contains("You don't seem to be solving the right level") {
+        return reply(Verdict::AlreadySolved);
+    }
+    let left = Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    if let Some(c) = left.captures(&text) {
+        let minutes: u64 = c.get(1).map_or(Ok(0), |m| m.as_str().parse())?;
+        let wait = Duration::from_secs(minutes * 60 + c[2].parse::<u64>()?);
+        return Ok(Reply {
+            verdict: Verdict::Wait(wait),
+            cooldown: wait,
+        });
+    }
+    let mut excerpt: String = text.chars().take(200).collect();
+    if excerpt.is_empty() {
+        excerpt = "an empty page".into();
+    }
+    bail!("Unrecognised reply: {}", excerpt)
+}
+
+/// One logged submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub cooldown: Duration,
+}
+
+impl fmt::Display for Submission {
+    /// One tab-separated line of the log.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.time,
+            self.day,
+            self.part,
+            self.verdict,
+            self.cooldown.as_secs(),
+            self.answer
+        )
+    }
+}
+
+impl FromStr for Submission {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> anyhow::Result<Submission> {
+        let fields: Vec<&str> = line.splitn(6, '\t').collect();
+        let [time, day, part, verdict, cooldown, answer] = fields[..] else {
+            bail!("Expected 6 tab-separated fields, found {}", fields.len());
+        };
+        let number = |name: &str, value: &str| {
+            value
+                .parse::<u64>()
+                .with_context(|| format!("Invalid {} '{}'", name, value))
+        };
+        Ok(Submission {
+            time: number("time", time)?,
+            day: number("day", day)? as u32,
+            part: number("part", part)? as u32,
+            answer: answer.to_string(),
+            verdict: verdict.parse()?,
+            cooldown: Duration::from_secs(number("cooldown", cooldown)?),
+        })
+    }
+}
+
+/// Every submission made so far, oldest first.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SubmissionLog {
+    pub submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let submissions = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(n, line)| line.parse().with_context(|| format!("Line {}", n + 1)))
+            .collect::<anyhow::Result<_>>()?;
+        Ok(SubmissionLog { submissions })
+    }
+
+    /// Checks whether `answer` is worth sending at `time`. Returns the
+    /// verdict without sending when it is already known to be correct.
+    pub fn check(
+        &self,
+        day: u32,
+        part: u32,
+        answer: &Answer,
+        time: u64,
+    ) -> anyhow::Result<Option<Verdict>> {
+        let text = answer.to_string();
+        let value = number(answer);
+        let previous = self
+            .submissions
+            .iter()
+            .filter(|s| (s.day, s.part) == (day, part));
+
+        for submission in previous {
+            let same = submission.answer == text;
+            let bound = submission
+                .answer
+                .parse::<Answer>()
+                .ok()
+                .and_then(|a| number(&a));
+            match submission.verdict {
+                Verdict::Correct if same => return Ok(Some(Verdict::Correct)),
+                Verdict::Correct => bail!(
+                    "Day {} part {} was already solved with {}",
+                    day,
+                    part,
+                    submission.answer
+                ),
+                verdict if same && verdict.is_wrong() => bail!(
+                    "{} was already rejected for day {} part {}: {}",
+                    text,
+                    day,
+                    part,
+                    verdict
+                ),
+                Verdict::TooHigh => {
+                    if let (Some(value), Some(bound)) = (&value, bound) {
+                        if *value >= bound {
+                            bail!("{} is not below {}, which was too high", text, bound);
+                        }
+                    }
+                }
+                Verdict::TooLow => {
+                    if let (Some(value), Some(bound)) = (&value, bound) {
+                        if *value <= bound {
+                            bail!("{} is not above {}, which was too low", text, bound);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(last) = self.submissions.last() {
+            let ready = last.time + last.cooldown.as_secs();
+            if time < ready {
+                bail!(
+                    "The last answer was {}, wait {}s before submitting again",
+                    last.verdict,
+                    ready - time
+                );
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn number(answer: &Answer) -> Option<BigInt> {
+    match answer {
+        Answer::Integer(n) => Some(BigInt::from(*n)),
+        Answer::BigInteger(n) => Some(n.clone()),
+        Answer::Text(_) => None,
+    }
+}
+
+/// Submits answers for one year and one session, through the log.
+///
+/// Not `Debug`, to keep the session token out of logs.
+#[derive(Clone)]
+pub struct Submitter {
+    session: String,
+    year: u32,
+    base_url: String,
+    cache_dir: PathBuf,
+    min_interval: Duration,
+}
+
+impl Submitter {
+    /// A submitter for 2025 with the default endpoint, logging in `input/`,
+    /// where it shares the spacing of requests with [`inputs::Fetcher`].
+    pub fn new(session: impl Into<String>) -> Self {
+        Submitter {
+            session: session.into().trim().to_string(),
+            year: 2025,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cache_dir: PathBuf::from("input"),
+            min_interval: Duration::from_secs(5),
+        }
+    }
+
+    /// A submitter using the session in [`inputs::SESSION_VAR`], and the
+    /// endpoint in [`inputs::BASE_URL_VAR`] if set.
+    pub fn from_env() -> anyhow::Result<Self> {
+        let submitter = Submitter::new(inputs::session_from_env()?);
+        Ok(match std::env::var(inputs::BASE_URL_VAR) {
+            Ok(base_url) => submitter.with_base_url(base_url),
+            Err(_) => submitter,
+        })
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = cache_dir.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    /// `input/2025/submissions.log`.
+    pub fn log_path(&self) -> PathBuf {
+        self.cache_dir
+            .join(self.year.to_string())
+            .join("submissions.log")
+    }
+
+    pub fn log(&self) -> anyhow::Result<SubmissionLog> {
+        let path = self.log_path();
+        if !path.exists() {
+            return Ok(SubmissionLog::default());
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        SubmissionLog::parse(&text).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    /// Sends `answer` unless the log shows it cannot be right, and logs the
+    /// reply.
+    pub fn submit(&self, day: u32, part: u32, answer: &Answer) -> anyhow::Result<Verdict> {
+        if !(1..=25).contains(&day) {
+            bail!("There is no day {}", day);
+        }
+        if !(1..=2).contains(&part) {
+            bail!("There is no part {}", part);
+        }
+        let text = answer.to_string();
+        if text.is_empty() || text.contains(char::is_whitespace) {
+            bail!("'{}' cannot be submitted, answers are a single word", text);
+        }
+        if self.session.is_empty() {
+            bail!("The session token is empty");
+        }
+        if let Some(verdict) = self.log()?.check(day, part, answer, now())? {
+            return Ok(verdict);
+        }
+
+        inputs::wait_for_turn(&self.cache_dir, self.min_interval)?;
+        let url = format!("{}/{}/day/{}/answer", self.base_url, self.year, day);
+        let response = ureq::post(&url)
+            .set("User-Agent", &inputs::user_agent())
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", &text)])
+            .map_err(|e| inputs::request_error(&self.cache_dir, &url, e))?;
+        let html = response
+            .into_string()
+            .with_context(|| format!("Failed to read the response from {}", url))?;
+        let reply = parse_reply(&html)?;
+
+        self.append(&Submission {
+            time: now(),
+            day,
+            part,
+            answer: text,
+            verdict: reply.verdict,
+            cooldown: reply.cooldown,
+        })?;
+        Ok(reply.verdict)
+    }
+
+    fn append(&self, submission: &Submission) -> anyhow::Result<()> {
+        let path = self.log_path();
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut log| writeln!(log, "{}", submission))
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::{response, stub_server};
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    fn cache_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("aoc2025-submit-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn replies() {
+        let verdict = |message: &str| parse_reply(&page(message)).unwrap();
+        assert_eq!(
+            verdict("That's the right answer!  You are <span>one gold star</span> closer."),
+            Reply {
+                verdict: Verdict::Correct,
+                cooldown: Duration::ZERO
+            }
+        );
+        assert_eq!(
+            verdict(
+                "That's not the right answer; your answer is too high.  If you're stuck, \
+                 make sure you're using the full input data.  Please wait one minute \
+                 before trying again. <a href=\"/2025/day/5\">[Return to Day 5]</a>"
+            ),
+            Reply {
+                verdict: Verdict::TooHigh,
+                cooldown: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(
+            verdict(
+                "That's not the right answer; your answer is too low.  Please wait\n\
+                 5 minutes before trying again."
+            ),
+            Reply {
+                verdict: Verdict::TooLow,
+                cooldown: Duration::from_secs(300)
+            }
+        );
+        assert_eq!(
+            verdict("That's not the right answer.  (You guessed <span>12.</span>)").verdict,
+            Verdict::Wrong
+        );
+        assert_eq!(
+            verdict("You don't seem to be solving the right level.  Did you already complete it?")
+                .verdict,
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            verdict(
+                "You gave an answer too recently; you have to wait after submitting an \
+                 answer before trying again.  You have 1m 5s left to wait."
+            ),
+            Reply {
+                verdict: Verdict::Wait(Duration::from_secs(65)),
+                cooldown: Duration::from_secs(65)
+            }
+        );
+        assert_eq!(
+            verdict("You have 37s left to wait.").verdict,
+            Verdict::Wait(Duration::from_secs(37))
+        );
+        assert_eq!(
+            parse_reply(&page("<b>Maintenance</b>"))
+                .err()
+                .unwrap()
+                .to_string(),
+            "Unrecognised reply: Maintenance"
+        );
+    }
+
+    #[test]
+    fn log_round_trip() {
+        let submission = Submission {
+            time: 1764547200,
+            day: 5,
+            part: 2,
+            answer: "abc def".into(),
+            verdict: Verdict::Wait(Duration::from_secs(30)),
+            cooldown: Duration::from_secs(30),
+        };
+        let line = submission.to_string();
+        assert_eq!(line, "1764547200\t5\t2\twait 30s\t30\tabc def");
+        let log = SubmissionLog::parse(&format!("{}\n\n{}\n", line, line)).unwrap();
+        assert_eq!(log.submissions, [submission.clone(), submission]);
+        assert_eq!(
+            format!(
+                "{:#}",
+                SubmissionLog::parse("1\t5\t2\tmaybe\t0\t3").unwrap_err()
+            ),
+            "Line 1: Unknown verdict 'maybe'"
+        );
+        assert_eq!(
+            format!("{:#}", SubmissionLog::parse("1\t5\t2").unwrap_err()),
+            "Line 1: Expected 6 tab-separated fields, found 3"
+        );
+    }
+
+    #[test]
+    fn known_answers_are_not_sent() {
+        let log = |verdict, answer: &str| Submission {
+            time: 1000,
+            day: 1,
+            part: 1,
+            answer: answer.into(),
+            verdict,
+            cooldown: Duration::from_secs(60),
+        };
+        let log = SubmissionLog {
+            submissions: vec![
+                log(Verdict::TooHigh, "500"),
+                log(Verdict::TooLow, "100"),
+                log(Verdict::Wrong, "300"),
+                log(Verdict::Wait(Duration::from_secs(10)), "200"),
+            ],
+        };
+        let check = |answer: &str, time| {
+            log.check(1, 1, &answer.parse().unwrap(), time)
+                .map_err(|e| e.to_string())
+        };
+        assert_eq!(
+            check("300", 2000).unwrap_err(),
+            "300 was already rejected for day 1 part 1: wrong"
+        );
+        assert_eq!(
+            check("600", 2000).unwrap_err(),
+            "600 is not below 500, which was too high"
+        );
+        assert_eq!(
+            check("100", 2000).unwrap_err(),
+            "100 was already rejected for day 1 part 1: too low"
+        );
+        assert_eq!(
+            check("99", 2000).unwrap_err(),
+            "99 is not above 100, which was too low"
+        );
+        assert_eq!(
+            check("200", 1005).unwrap_err(),
+            "The last answer was wait 10s, wait 55s before submitting again"
+        );
+        assert_eq!(check("200", 2000), Ok(None));
+        assert_eq!(check("abc", 2000), Ok(None));
+        // other parts have their own history
+        assert_eq!(log.check(1, 2, &Answer::from(600), 2000).unwrap(), None);
+
+        let solved = SubmissionLog {
+            submissions: vec![Submission {
+                verdict: Verdict::Correct,
+                cooldown: Duration::ZERO,
+                ..log.submissions[0].clone()
+            }],
+        };
+        assert_eq!(
+            solved.check(1, 1, &Answer::from(500), 2000).unwrap(),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(
+            solved
+                .check(1, 1, &Answer::from(400), 2000)
+                .unwrap_err()
+                .to_string(),
+            "Day 1 part 1 was already solved with 500"
+        );
+    }
+
+    #[test]
+    fn submits_and_logs_replies() {
+        let (url, requests) = stub_server(vec![
+            response(
+                "200 OK",
+                "",
+                &page("That's not the right answer; your answer is too low."),
+            ),
+            response("200 OK", "", &page("That's the right answer!")),
+            response("403 Forbidden", "", ""),
+        ]);
+        let dir = cache_dir("submit");
+        let submitter = Submitter::new("abc123")
+            .with_base_url(&url)
+            .with_cache_dir(&dir)
+            .with_min_interval(Duration::ZERO);
+
+        assert_eq!(
+            submitter.submit(6, 2, &Answer::from(41)).unwrap(),
+            Verdict::TooLow
+        );
+        let request = requests.recv().unwrap();
+        assert!(
+            request.starts_with("POST /2025/day/6/answer HTTP/1.1\r\n"),
+            "{}",
+            request
+        );
+        assert!(
+            request.contains("Cookie: session=abc123\r\n"),
+            "{}",
+            request
+        );
+        assert!(request.ends_with("\r\nlevel=2&answer=41"), "{}", request);
+
+        // refused without a request
+        assert_eq!(
+            submitter
+                .submit(6, 2, &Answer::from(40))
+                .unwrap_err()
+                .to_string(),
+            "40 is not above 41, which was too low"
+        );
+        assert_eq!(
+            submitter.submit(6, 2, &Answer::from(42)).unwrap(),
+            Verdict::Correct
+        );
+        assert!(requests.recv().unwrap().ends_with("level=2&answer=42"));
+        assert_eq!(
+            submitter.submit(6, 2, &Answer::from(42)).unwrap(),
+            Verdict::Correct
+        );
+
+        let log = submitter.log().unwrap();
+        assert_eq!(
+            log.submissions
+                .iter()
+                .map(|s| (s.answer.as_str(), s.verdict))
+                .collect::<Vec<_>>(),
+            [("41", Verdict::TooLow), ("42", Verdict::Correct)]
+        );
+
+        assert_eq!(
+            submitter
+                .submit(6, 1, &Answer::from(7))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "{}/2025/day/6/answer rejected the session token, log in again and update it",
+                url
+            )
+        );
+        assert_eq!(
+            submitter
+                .submit(6, 1, &Answer::from("a b"))
+                .unwrap_err()
+                .to_string(),
+            "'a b' cannot be submitted, answers are a single word"
+        );
+        assert_eq!(submitter.log().unwrap().submissions.len(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}